@@ -0,0 +1,152 @@
+// Base32 decoding over a caller-chosen 32-character alphabet. Filecoin uses
+// lowercase RFC 4648; other chains differ only in alphabet and padding, so
+// they pass their own alphabet rather than re-implementing the regrouping.
+use std::fmt;
+
+// The digit values 0-31, in order
+pub type Alphabet = [u8; 32];
+
+// RFC 4648 section 6 in lowercase, as Filecoin writes it
+pub const RFC4648_LOWER: &Alphabet = b"abcdefghijklmnopqrstuvwxyz234567";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    InvalidChar(char, usize),
+    // A length no whole number of bytes encodes to (unpadded), or that isn't
+    // a multiple of 8 (padded)
+    Length(usize),
+    Padding,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidChar(c, i) => write!(f, "invalid character '{}' at position {}", c, i),
+            Error::Length(len) => write!(f, "{} characters don't encode whole bytes", len),
+            Error::Padding => write!(f, "invalid '=' padding"),
+        }
+    }
+}
+
+// Decode `s`, which ends in `=` padding to a multiple of 8 characters when
+// `padded` and has none otherwise. Bits left over after the last whole byte
+// are dropped, as RFC 4648 decoders do.
+pub fn decode(s: &str, alphabet: &Alphabet, padded: bool) -> Result<Vec<u8>, Error> {
+    let data = if padded {
+        if !s.len().is_multiple_of(8) {
+            return Err(Error::Length(s.len()));
+        }
+        let data = s.trim_end_matches('=');
+        // Only these amounts of padding complete a final 8-character group
+        if ![0, 1, 3, 4, 6].contains(&(s.len() - data.len())) {
+            return Err(Error::Padding);
+        }
+        data
+    } else {
+        s
+    };
+    let mut out = Vec::with_capacity(data.len() * 5 / 8);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for (i, c) in data.char_indices() {
+        let value = alphabet
+            .iter()
+            .position(|&b| b as char == c)
+            .ok_or(Error::InvalidChar(c, i))?;
+        acc = (acc << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    // A final group of 1, 3 or 6 characters leaves a byte partly encoded
+    if [1, 3, 6].contains(&(data.len() % 8)) {
+        return Err(Error::Length(data.len()));
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RFC4648_UPPER: &Alphabet = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+    // RFC 4648 section 10
+    const VECTORS: &[(&str, &str)] = &[
+        ("", ""),
+        ("f", "MY======"),
+        ("fo", "MZXQ===="),
+        ("foo", "MZXW6==="),
+        ("foob", "MZXW6YQ="),
+        ("fooba", "MZXW6YTB"),
+        ("foobar", "MZXW6YTBOI======"),
+    ];
+
+    #[test]
+    fn decodes_the_rfc4648_vectors() {
+        for (decoded, encoded) in VECTORS {
+            let unpadded = encoded.trim_end_matches('=');
+            assert_eq!(
+                decode(encoded, RFC4648_UPPER, true).as_deref(),
+                Ok(decoded.as_bytes())
+            );
+            assert_eq!(
+                decode(unpadded, RFC4648_UPPER, false).as_deref(),
+                Ok(decoded.as_bytes())
+            );
+            assert_eq!(
+                decode(&unpadded.to_lowercase(), RFC4648_LOWER, false).as_deref(),
+                Ok(decoded.as_bytes())
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_characters_outside_the_alphabet() {
+        assert_eq!(
+            decode("MZXW6YTB", RFC4648_LOWER, false),
+            Err(Error::InvalidChar('M', 0))
+        );
+        assert_eq!(
+            decode("mzxw1ytb", RFC4648_LOWER, false),
+            Err(Error::InvalidChar('1', 4))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_padding_and_lengths() {
+        // Two '=' can't complete a group, and padding needs whole groups
+        assert_eq!(decode("MZXW6Y==", RFC4648_UPPER, true), Err(Error::Padding));
+        assert_eq!(
+            decode("MZXW6YQ", RFC4648_UPPER, true),
+            Err(Error::Length(7))
+        );
+        assert_eq!(
+            decode("MZXW6YQ=", RFC4648_UPPER, false),
+            Err(Error::InvalidChar('=', 7))
+        );
+        // One character leaves a byte only partly encoded
+        assert_eq!(
+            decode("MZXW6YTBO", RFC4648_UPPER, false),
+            Err(Error::Length(9))
+        );
+    }
+
+    #[test]
+    fn decodes_a_filecoin_payload() {
+        // The base32 part of f1abjxfbp274xpdqcpuaykwkfb43omjotacm2p3za: a
+        // 20-byte secp256k1 key hash and its 4-byte Blake2b checksum
+        let decoded = decode(
+            "abjxfbp274xpdqcpuaykwkfb43omjotacm2p3za",
+            RFC4648_LOWER,
+            false,
+        );
+        assert_eq!(
+            decoded.map(hex::encode).as_deref(),
+            Ok("00537285faff2ef1c04fa030ab28a1e6dcc4ba601334fde4")
+        );
+    }
+}
@@ -0,0 +1,96 @@
+// Base58Check encoding and decoding with a pluggable checksum hash
+use blake_hash::Blake256;
+use groestl::Groestl512;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+// Computes the 4-byte checksum appended to a Base58Check payload
+pub type ChecksumFn = fn(&[u8]) -> [u8; 4];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    InvalidChar(char, usize),
+    TooShort,
+    InvalidChecksum,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidChar(c, i) => write!(f, "invalid character '{}' at position {}", c, i),
+            Error::TooShort => write!(f, "too short to hold a checksum"),
+            Error::InvalidChecksum => write!(f, "checksum mismatch"),
+        }
+    }
+}
+
+// Bitcoin's checksum: the first four bytes of SHA-256(SHA-256(payload))
+pub fn double_sha256(payload: &[u8]) -> [u8; 4] {
+    let hash = Sha256::digest(Sha256::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+// Groestlcoin's checksum: the first four bytes of Groestl-512(Groestl-512(payload))
+pub fn double_groestl512(payload: &[u8]) -> [u8; 4] {
+    let hash = Groestl512::digest(Groestl512::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+// Decred's checksum: the first four bytes of BLAKE-256(BLAKE-256(payload))
+pub fn double_blake256(payload: &[u8]) -> [u8; 4] {
+    use blake_hash::Digest as _;
+
+    let hash = Blake256::digest(&Blake256::digest(payload));
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+// Decode `s` and verify its trailing checksum, returning the payload
+// (version bytes included) without the checksum.
+pub fn decode(s: &str, checksum: ChecksumFn) -> Result<Vec<u8>, Error> {
+    let mut bytes = bs58::decode(s).into_vec().map_err(|err| match err {
+        bs58::decode::Error::InvalidCharacter { character, index } => {
+            Error::InvalidChar(character, index)
+        }
+        _ => Error::TooShort,
+    })?;
+    if bytes.len() < 5 {
+        return Err(Error::TooShort);
+    }
+
+    let payload_len = bytes.len() - 4;
+    if checksum(&bytes[..payload_len]) != bytes[payload_len..] {
+        return Err(Error::InvalidChecksum);
+    }
+    bytes.truncate(payload_len);
+    Ok(bytes)
+}
+
+// Append the checksum of `payload` and Base58-encode the result
+pub fn encode(payload: &[u8], checksum: ChecksumFn) -> String {
+    let mut bytes = payload.to_vec();
+    bytes.extend_from_slice(&checksum(payload));
+    bs58::encode(bytes).into_string()
+}
+
+// Every (position, replacement) where changing one character of `s` gives a
+// valid checksum. Typos are usually a single character, and trying each
+// substitution is only a few thousand decodes for an address.
+pub fn single_substitutions(s: &str, checksum: ChecksumFn) -> Vec<(usize, char)> {
+    let mut candidate = s.as_bytes().to_vec();
+    let mut fixes = Vec::new();
+    for i in 0..candidate.len() {
+        let original = candidate[i];
+        for &replacement in ALPHABET.iter().filter(|&&c| c != original) {
+            candidate[i] = replacement;
+            let fixed =
+                std::str::from_utf8(&candidate).is_ok_and(|fixed| decode(fixed, checksum).is_ok());
+            if fixed {
+                fixes.push((i, replacement as char));
+            }
+        }
+        candidate[i] = original;
+    }
+    fixes
+}
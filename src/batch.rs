@@ -0,0 +1,942 @@
+use crate::i18n::{self, Mark, Msg};
+use crate::output;
+use crate::{
+    canonical_address, find_chain, slip44_coin_type, validate_address, validate_into, Args, Chain,
+    InputFormat, OutputFormat, Report, ValidationOptions, ValidationResult, CHAINS,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde_json::{json, Map, Value};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read};
+use std::process;
+use std::rc::Rc;
+use std::time::Instant;
+
+// Running counts of a batch run, for the printed summary and --summary-out
+#[derive(Default)]
+struct Tally {
+    total: usize,
+    valid: usize,
+    per_chain: Vec<(&'static Chain, usize)>,
+    // Unlabeled addresses no chain accepted, when detecting among several
+    undetected: usize,
+}
+
+impl Tally {
+    // Count one address and the chains that accepted it
+    fn add(&mut self, matches: &[&'static Chain]) {
+        self.total += 1;
+        if !matches.is_empty() {
+            self.valid += 1;
+        }
+        for chain in matches {
+            match self
+                .per_chain
+                .iter_mut()
+                .find(|(c, _)| c.name == chain.name)
+            {
+                Some((_, count)) => *count += 1,
+                None => self.per_chain.push((chain, 1)),
+            }
+        }
+    }
+
+    fn all_valid(&self) -> bool {
+        self.valid == self.total
+    }
+
+    // --summary-out: the final counts as a JSON file
+    fn write_summary(&self, args: &Args, chains: &[&'static Chain], started: Instant) {
+        let Some(path) = &args.summary_out else {
+            return;
+        };
+        let per_chain: Map<String, Value> = self
+            .per_chain
+            .iter()
+            .map(|(chain, count)| (chain.name.to_string(), json!(count)))
+            .collect();
+        let mut summary = json!({
+            "total": self.total,
+            "valid": self.valid,
+            "invalid": self.total - self.valid,
+            "per_chain": per_chain,
+            "duration_ms": started.elapsed().as_millis() as u64,
+        });
+        if chains.len() > 1 {
+            summary["undetected"] = json!(self.undetected);
+        }
+        let contents = serde_json::to_string_pretty(&summary).expect("summaries always serialize");
+        if let Err(err) = std::fs::write(path, contents + "\n") {
+            eprintln!("Failed to write {}: {}", path, err);
+            process::exit(1);
+        }
+    }
+}
+
+// Validate each address in `path` ("-" for stdin) against `chains`, one per
+// line unless --delimiter says otherwise.
+// With several chains, an address counts as valid if any of them accepts it.
+// Returns whether every address was valid.
+pub fn run(
+    args: &Args,
+    path: &str,
+    chains: &[&'static Chain],
+    options: &ValidationOptions,
+) -> bool {
+    let input = match open_input(path) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("Failed to open {}: {}", path, err);
+            process::exit(1);
+        }
+    };
+
+    let started = Instant::now();
+    let progress = progress_bar(args, path);
+    let mut tally = Tally::default();
+
+    match args.input_format {
+        InputFormat::Ndjson => {
+            run_ndjson(args, input, path, chains, options, &progress, &mut tally)
+        }
+        InputFormat::JsonArray => {
+            run_json_array(args, input, path, chains, options, &progress, &mut tally)
+        }
+        InputFormat::Csv => run_csv(args, input, path, chains, options, &progress, &mut tally),
+        _ => {
+            run_lines(args, input, path, chains, options, &progress, &mut tally);
+        }
+    }
+    tally.write_summary(args, chains, started);
+
+    tally.all_valid()
+}
+
+// The `checksum` command: the checksummed form of each valid address, one
+// per line, for cleaning up stored addresses. Invalid addresses are listed
+// with what failed rather than dropped, and no summary is printed, so the
+// output lines up with the input. Returns whether every address was valid.
+pub fn run_checksum(
+    args: &Args,
+    path: &str,
+    chain: &'static Chain,
+    options: &ValidationOptions,
+) -> bool {
+    let input = match open_input(path) {
+        Ok(input) => input,
+        Err(err) => {
+            eprintln!("Failed to open {}: {}", path, err);
+            process::exit(1);
+        }
+    };
+    let progress = progress_bar(args, path);
+    let mut tally = Tally::default();
+    let mut scratch = ValidationResult::new();
+
+    for (number, line) in numbered_lines(input, path, &progress) {
+        let address = line.as_deref().map(str::trim);
+        if address == Some("") {
+            continue;
+        }
+        let failure = match address {
+            Some(address) => {
+                validate_into(chain, address, options, &mut scratch);
+                (!scratch.valid).then(|| {
+                    scratch
+                        .details
+                        .iter()
+                        .find(|check| !check.passed)
+                        .map(|check| format!("{}: {}", check.name, check.message))
+                        .unwrap_or_default()
+                })
+            }
+            None => Some(i18n::text(Msg::NotUtf8).to_string()),
+        };
+        let matches = if failure.is_none() {
+            vec![chain]
+        } else {
+            Vec::new()
+        };
+        tally.add(&matches);
+        update_progress(&progress, tally.total);
+
+        let address = address.unwrap_or_default();
+        let output = match (args.format, failure) {
+            (OutputFormat::Json, None) => json!({
+                "line": number,
+                "address": address,
+                "checksummed": canonical_address(chain, address, options),
+            })
+            .to_string(),
+            (OutputFormat::Json, Some(failure)) => json!({
+                "line": number,
+                "address": address,
+                "error": failure,
+            })
+            .to_string(),
+            (_, None) => canonical_address(chain, address, options),
+            (_, Some(failure)) => format!(
+                "{} {} ({}: {})",
+                i18n::mark(Mark::Invalid),
+                address,
+                i18n::fill(Msg::Line, &[&number]),
+                failure
+            ),
+        };
+        progress.suspend(|| outln!("{}", output));
+    }
+    progress.finish_and_clear();
+
+    tally.all_valid()
+}
+
+// Plain and labeled input, listing each address and ending with a summary
+fn run_lines(
+    args: &Args,
+    input: Box<dyn BufRead>,
+    path: &str,
+    chains: &[&'static Chain],
+    options: &ValidationOptions,
+    progress: &ProgressBar,
+    tally: &mut Tally,
+) {
+    let mut labeled = false;
+    let mut listing = Listing::new(args);
+    // Every address is validated into this one result, so its lists are
+    // allocated once per run rather than once per address and chain
+    let mut scratch = ValidationResult::new();
+
+    for (number, line) in numbered_lines(input, path, progress) {
+        let Some(line) = line else {
+            tally.add(&[]);
+            if !args.count_only && !args.only_valid {
+                listing.push(
+                    false,
+                    format!(
+                        "{} {}: {}",
+                        i18n::mark(Mark::Invalid),
+                        i18n::fill(Msg::Line, &[&number]),
+                        i18n::text(Msg::NotUtf8)
+                    ),
+                    progress,
+                );
+            }
+            continue;
+        };
+        for token in args.delimiter.split(&line) {
+            let line = token.trim();
+            if line.is_empty() {
+                continue;
+            }
+            update_progress(progress, tally.total + 1);
+
+            let (label, address) = match args.input_format {
+                InputFormat::Labeled => split_label(line),
+                _ => (None, line),
+            };
+            let line_chains = match label {
+                None => chains.to_vec(),
+                Some(label) => match find_chain(&label.to_lowercase()) {
+                    Some(chain) => {
+                        labeled = true;
+                        vec![chain]
+                    }
+                    None => {
+                        tally.add(&[]);
+                        if !args.count_only && !args.only_valid {
+                            listing.push(
+                                false,
+                                format!(
+                                    "{} {}: {}",
+                                    i18n::mark(Mark::Invalid),
+                                    i18n::fill(Msg::Line, &[&number]),
+                                    i18n::fill(Msg::UnknownLabel, &[&address, &label])
+                                ),
+                                progress,
+                            );
+                        }
+                        continue;
+                    }
+                },
+            };
+
+            let matches: Vec<&'static Chain> = line_chains
+                .iter()
+                .filter(|chain| {
+                    validate_into(chain, address, options, &mut scratch);
+                    scratch.valid
+                })
+                .copied()
+                .take(match_limit(args))
+                .collect();
+
+            tally.add(&matches);
+            if matches.is_empty() && label.is_none() && line_chains.len() > 1 {
+                tally.undetected += 1;
+            }
+
+            if !args.count_only && shown(args, !matches.is_empty()) {
+                listing.push(
+                    !matches.is_empty(),
+                    format_line(
+                        number,
+                        address,
+                        label,
+                        line_chains.len() > 1,
+                        &matches,
+                        options,
+                    ),
+                    progress,
+                );
+            }
+        }
+    }
+    progress.finish_and_clear();
+    listing.print_groups();
+
+    if !args.count_only {
+        outln!();
+    }
+    let invalid = tally.total - tally.valid;
+    outln!(
+        "{}",
+        i18n::fill(Msg::Summary, &[&tally.total, &tally.valid, &invalid])
+    );
+    if chains.len() > 1 || labeled {
+        for (chain, count) in &tally.per_chain {
+            outln!("- {} ({}): {}", chain.label, chain.name, count);
+        }
+        if chains.len() > 1 {
+            outln!(
+                "- {}",
+                i18n::fill(Msg::UndetectedCount, &[&tally.undetected])
+            );
+        }
+    }
+    if !args.quiet {
+        outln!(
+            "RESULT total={} valid={} invalid={}",
+            tally.total,
+            tally.valid,
+            invalid
+        );
+    }
+}
+
+// Each input line is a JSON object with an "address" and an optional
+// "blockchain". Each output line is that object with the report fields and
+// its 1-based "line" number added, so ids and other caller fields pass
+// through. No summary is printed,
+// keeping the output a clean NDJSON stream.
+fn run_ndjson(
+    args: &Args,
+    input: Box<dyn BufRead>,
+    path: &str,
+    chains: &[&'static Chain],
+    options: &ValidationOptions,
+    progress: &ProgressBar,
+    tally: &mut Tally,
+) {
+    for (number, line) in numbered_lines(input, path, progress) {
+        let result = match &line {
+            Some(line) if line.trim().is_empty() => continue,
+            Some(line) => match serde_json::from_str(line) {
+                Ok(value) => validate_json_value(args, value, chains, options),
+                Err(err) => Err(format!("invalid JSON: {}", err)),
+            },
+            None => Err(i18n::text(Msg::NotUtf8).to_string()),
+        };
+        update_progress(progress, tally.total + 1);
+
+        let (output, valid) = match result {
+            Ok((mut output, chain, valid)) => {
+                let matches = if valid { vec![chain] } else { Vec::new() };
+                tally.add(&matches);
+                output["line"] = json!(number);
+                (output, valid)
+            }
+            Err(err) => {
+                tally.add(&[]);
+                (json!({ "line": number, "error": err }), false)
+            }
+        };
+        if shown(args, valid) {
+            progress.suspend(|| outln!("{}", output));
+        }
+    }
+    progress.finish_and_clear();
+}
+
+// The whole input is one JSON array, validated element by element into a
+// single result array. Elements are objects as in NDJSON or bare address
+// strings. Each result carries the element's 1-based position as its "line",
+// as NDJSON and CSV results do; an element that can't be validated becomes an
+// object with its "line" and an "error".
+fn run_json_array(
+    args: &Args,
+    mut input: Box<dyn BufRead>,
+    path: &str,
+    chains: &[&'static Chain],
+    options: &ValidationOptions,
+    progress: &ProgressBar,
+    tally: &mut Tally,
+) {
+    let mut contents = String::new();
+    if let Err(err) = input.read_to_string(&mut contents) {
+        eprintln!("Failed to read {}: {}", path, err);
+        process::exit(1);
+    }
+    progress.set_position(contents.len() as u64);
+    let elements = match serde_json::from_str(&contents) {
+        Ok(Value::Array(elements)) => elements,
+        Ok(_) => {
+            eprintln!("Failed to read {}: expected a JSON array", path);
+            process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("Failed to read {}: invalid JSON: {}", path, err);
+            process::exit(1);
+        }
+    };
+
+    let mut results = Vec::with_capacity(elements.len());
+    for (index, element) in elements.into_iter().enumerate() {
+        let line = index + 1;
+        let element = match element {
+            Value::String(address) => json!({ args.column.as_str(): address }),
+            element => element,
+        };
+        update_progress(progress, tally.total + 1);
+
+        let (output, valid) = match validate_json_value(args, element, chains, options) {
+            Ok((mut output, chain, valid)) => {
+                let matches = if valid { vec![chain] } else { Vec::new() };
+                tally.add(&matches);
+                output["line"] = json!(line);
+                (output, valid)
+            }
+            Err(err) => {
+                tally.add(&[]);
+                (json!({ "line": line, "error": err }), false)
+            }
+        };
+        if shown(args, valid) {
+            results.push(output);
+        }
+    }
+    progress.finish_and_clear();
+    outln!("{}", Value::Array(results));
+}
+
+// Validate one JSON object's address, returning the object with the report
+// fields added
+fn validate_json_value(
+    args: &Args,
+    value: Value,
+    chains: &[&'static Chain],
+    options: &ValidationOptions,
+) -> Result<(Value, &'static Chain, bool), String> {
+    let Value::Object(mut object) = value else {
+        return Err("expected a JSON object".to_string());
+    };
+
+    let address = match object.get(&args.column) {
+        Some(Value::String(address)) => address.clone(),
+        _ => return Err(format!("missing \"{}\" string", args.column)),
+    };
+    let chain = match object.get("blockchain") {
+        Some(Value::String(name)) => {
+            find_chain(name).ok_or_else(|| format!("unsupported blockchain '{}'", name))?
+        }
+        Some(_) => return Err("\"blockchain\" must be a string".to_string()),
+        None if chains.len() == 1 => chains[0],
+        None => return Err("no \"blockchain\" given and --blockchain is all".to_string()),
+    };
+
+    let result = validate_address(chain, &address, options);
+    let report = Report {
+        blockchain: chain.name,
+        slip44: slip44_coin_type(chain).filter(|_| args.slip44),
+        address: &address,
+        result: &result,
+    };
+    if let Ok(Value::Object(fields)) = serde_json::to_value(&report) {
+        object.extend(fields);
+    }
+    Ok((Value::Object(object), chain, result.valid))
+}
+
+// Each record's --column is validated against `chains` and the record is
+// written back with "valid", "matches" (the accepting chains, separated by
+// spaces) and "line" (the record's 1-based line in the input) appended. Like
+// NDJSON, the output is only the CSV itself. Fields are quoted as RFC 4180
+// has it, so commas, quotes and line breaks in caller fields survive, and
+// rows end in CRLF when the input's do.
+fn run_csv(
+    args: &Args,
+    mut input: Box<dyn BufRead>,
+    path: &str,
+    chains: &[&'static Chain],
+    options: &ValidationOptions,
+    progress: &ProgressBar,
+    tally: &mut Tally,
+) {
+    let crlf = input.fill_buf().is_ok_and(|buf| {
+        buf.split(|&b| b == b'\n')
+            .next()
+            .is_some_and(|line| line.ends_with(b"\r"))
+    });
+    let newlines = Rc::new(RefCell::new(VecDeque::new()));
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(NewlineOffsets {
+            inner: input,
+            read: 0,
+            newlines: Rc::clone(&newlines),
+        });
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(csv::QuoteStyle::Necessary)
+        .terminator(if crlf {
+            csv::Terminator::CRLF
+        } else {
+            csv::Terminator::Any(b'\n')
+        })
+        .from_writer(output::Output);
+    let fail = |err: csv::Error| -> ! {
+        eprintln!("Failed to read {}: {}", path, err);
+        process::exit(1);
+    };
+
+    let mut headers = reader.headers().unwrap_or_else(|err| fail(err)).clone();
+    let column = headers
+        .iter()
+        .position(|header| header == args.column)
+        .or_else(|| args.column.parse().ok().filter(|&i| i < headers.len()));
+    let Some(column) = column else {
+        eprintln!("No column '{}' in {}", args.column, path);
+        process::exit(1);
+    };
+    headers.push_field("valid");
+    headers.push_field("matches");
+    headers.push_field("line");
+    write_record(&mut writer, &headers, progress);
+
+    let mut scratch = ValidationResult::new();
+    // csv counts a record that follows a CRLF from the line before it, and
+    // starts it at the LF, so lines are numbered from the newlines up to and
+    // including each record's first byte
+    let mut line = 1;
+    for record in reader.records() {
+        let mut record = record.unwrap_or_else(|err| fail(err));
+        if let Some(position) = record.position() {
+            let mut newlines = newlines.borrow_mut();
+            while newlines
+                .front()
+                .is_some_and(|&offset| offset <= position.byte())
+            {
+                newlines.pop_front();
+                line += 1;
+            }
+            progress.set_position(position.byte());
+        }
+        update_progress(progress, tally.total + 1);
+
+        let address = record.get(column).unwrap_or_default().trim();
+        let matches: Vec<&'static Chain> = chains
+            .iter()
+            .filter(|chain| {
+                validate_into(chain, address, options, &mut scratch);
+                scratch.valid
+            })
+            .copied()
+            .take(match_limit(args))
+            .collect();
+        tally.add(&matches);
+        let valid = !matches.is_empty();
+        if shown(args, valid) {
+            let names: Vec<&str> = matches.iter().map(|chain| chain.name).collect();
+            record.push_field(if valid { "true" } else { "false" });
+            record.push_field(&names.join(" "));
+            record.push_field(&line.to_string());
+            write_record(&mut writer, &record, progress);
+        }
+    }
+    progress.finish_and_clear();
+    if writer.flush().is_err() {
+        process::exit(1);
+    }
+}
+
+// Records the byte offset of each newline read, for numbering CSV records.
+// Offsets are dropped once a record starts past them, so only those in the
+// reader's buffer are kept.
+struct NewlineOffsets<R> {
+    inner: R,
+    read: u64,
+    newlines: Rc<RefCell<VecDeque<u64>>>,
+}
+
+impl<R: Read> Read for NewlineOffsets<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        let offsets = buf[..n]
+            .iter()
+            .enumerate()
+            .filter(|(_, &b)| b == b'\n')
+            .map(|(i, _)| self.read + i as u64);
+        self.newlines.borrow_mut().extend(offsets);
+        self.read += n as u64;
+        Ok(n)
+    }
+}
+
+fn write_record(
+    writer: &mut csv::Writer<output::Output>,
+    record: &csv::StringRecord,
+    progress: &ProgressBar,
+) {
+    if let Err(err) = progress.suspend(|| writer.write_record(record)) {
+        match err.into_kind() {
+            csv::ErrorKind::Io(err) => output::fail(err),
+            _ => process::exit(1),
+        }
+    }
+}
+
+// The per-address lines of a plain or labeled run. They stream as they come,
+// or with --group-by-validity are held until the end and printed as a valid
+// and an invalid section, each in input order.
+struct Listing {
+    grouped: bool,
+    valid: Vec<String>,
+    invalid: Vec<String>,
+}
+
+impl Listing {
+    fn new(args: &Args) -> Self {
+        Self {
+            grouped: args.group_by_validity,
+            valid: Vec::new(),
+            invalid: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, valid: bool, line: String, progress: &ProgressBar) {
+        match (self.grouped, valid) {
+            (false, _) => progress.suspend(|| outln!("{}", line)),
+            (true, true) => self.valid.push(line),
+            (true, false) => self.invalid.push(line),
+        }
+    }
+
+    fn print_groups(&self) {
+        if !self.grouped {
+            return;
+        }
+        outln!("{}", i18n::fill(Msg::GroupValid, &[&self.valid.len()]));
+        for line in &self.valid {
+            outln!("{}", line);
+        }
+        outln!(
+            "\n{}",
+            i18n::fill(Msg::GroupInvalid, &[&self.invalid.len()])
+        );
+        for line in &self.invalid {
+            outln!("{}", line);
+        }
+    }
+}
+
+// Whether --only-valid / --only-invalid keep an address in the listing
+fn shown(args: &Args, valid: bool) -> bool {
+    !((args.only_valid && !valid) || (args.only_invalid && valid))
+}
+
+// A progress bar on stderr, hidden with --quiet or when stderr isn't a
+// terminal. A file's progress is measured in bytes read against its size, so
+// the ETA needs no pre-count of its lines; stdin has no known length and
+// only gets a spinner.
+fn progress_bar(args: &Args, path: &str) -> ProgressBar {
+    if args.quiet || !io::stderr().is_terminal() {
+        return ProgressBar::hidden();
+    }
+    let size = match path {
+        "-" => None,
+        _ => std::fs::metadata(path).ok().map(|metadata| metadata.len()),
+    };
+    match size {
+        Some(size) => ProgressBar::new(size).with_style(
+            ProgressStyle::with_template(
+                "[{elapsed_precise}] {wide_bar} {percent}% ETA {eta} {msg}",
+            )
+            .expect("template is valid"),
+        ),
+        None => ProgressBar::new_spinner().with_style(
+            ProgressStyle::with_template("{spinner} [{elapsed_precise}] {msg}")
+                .expect("template is valid"),
+        ),
+    }
+}
+
+// Show the addresses processed so far and their rate. Formatting the
+// message on every line would cost more than validating, so it's refreshed
+// every thousand addresses.
+fn update_progress(progress: &ProgressBar, total: usize) {
+    if progress.is_hidden() || !total.is_multiple_of(1000) {
+        return;
+    }
+    let rate = total as f64 / progress.elapsed().as_secs_f64().max(0.001);
+    progress.set_message(format!("{} addresses ({:.0}/s)", total, rate));
+}
+
+// Input lines with their 1-based numbers. A line that isn't valid UTF-8
+// comes back as None so one bad line doesn't end the run; read errors do.
+// Each line's bytes advance `progress`.
+fn numbered_lines<'a>(
+    input: Box<dyn BufRead>,
+    path: &'a str,
+    progress: &'a ProgressBar,
+) -> impl Iterator<Item = (usize, Option<String>)> + 'a {
+    input.split(b'\n').enumerate().map(move |(index, line)| {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                eprintln!("Failed to read {}: {}", path, err);
+                process::exit(1);
+            }
+        };
+        progress.inc(line.len() as u64 + 1);
+        (index + 1, String::from_utf8(line).ok())
+    })
+}
+
+fn open_input(path: &str) -> io::Result<Box<dyn BufRead>> {
+    if path == "-" {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+// How many accepting chains to collect per address. With --best-match or
+// --blockchain auto the first one found is the answer, so the rest aren't
+// validated at all.
+fn match_limit(args: &Args) -> usize {
+    if args.best_match || args.blockchain == "auto" {
+        1
+    } else {
+        usize::MAX
+    }
+}
+
+// Split a labeled line into its chain label and address. Addresses never
+// contain commas or whitespace, so any separator means a label is present.
+fn split_label(line: &str) -> (Option<&str>, &str) {
+    match line.split_once(|c: char| c == ',' || c.is_whitespace()) {
+        Some((label, address)) => (Some(label.trim()), address.trim()),
+        None => (None, line),
+    }
+}
+
+// Failures carry their 1-based line number, so they can be found in a large
+// input file
+fn format_line(
+    number: usize,
+    address: &str,
+    label: Option<&str>,
+    several: bool,
+    matches: &[&Chain],
+    options: &ValidationOptions,
+) -> String {
+    if !matches.is_empty() {
+        if several {
+            let names: Vec<&str> = matches.iter().map(|chain| chain.name).collect();
+            return format!(
+                "{} {} ({})",
+                i18n::mark(Mark::Valid),
+                address,
+                names.join(", ")
+            );
+        }
+        return format!("{} {}", i18n::mark(Mark::Valid), address);
+    }
+
+    // A labeled address that fails may simply carry the wrong label
+    let elsewhere: Vec<&str> = match label {
+        Some(_) => CHAINS
+            .iter()
+            .filter(|chain| validate_address(chain, address, options).valid)
+            .map(|chain| chain.name)
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let failed = format!(
+        "{} {}: {}",
+        i18n::mark(Mark::Invalid),
+        i18n::fill(Msg::Line, &[&number]),
+        address
+    );
+    match label {
+        Some(label) if !elsewhere.is_empty() => {
+            i18n::fill(Msg::Mislabeled, &[&failed, &label, &elsewhere.join(", ")])
+        }
+        None if several => i18n::fill(Msg::Undetected, &[&failed]),
+        _ => failed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::selected_chains;
+    use clap::Parser;
+
+    fn args(extra: &[&str]) -> Args {
+        let fixed = ["blockchain-validator", "-b", "btc", "-f", "-"];
+        Args::try_parse_from(fixed.iter().chain(extra)).unwrap()
+    }
+
+    #[test]
+    fn invalid_utf8_is_reported_with_its_line_and_later_lines_still_validate() {
+        let input: &[u8] = b"1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa\n\
+            \xff\xfe\x00\n\
+            bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n";
+        let chains = [find_chain("btc").unwrap()];
+        let mut tally = Tally::default();
+        let output = output::capture(|| {
+            run_lines(
+                &args(&[]),
+                Box::new(input),
+                "-",
+                &chains,
+                &ValidationOptions::default(),
+                &ProgressBar::hidden(),
+                &mut tally,
+            )
+        });
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[..3],
+            [
+                format!(
+                    "{} 1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+                    i18n::mark(Mark::Valid)
+                ),
+                format!("{} line 2: not valid UTF-8", i18n::mark(Mark::Invalid)),
+                format!(
+                    "{} bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                    i18n::mark(Mark::Valid)
+                ),
+            ]
+        );
+        assert_eq!((tally.total, tally.valid), (3, 2));
+    }
+
+    #[test]
+    fn auto_credits_each_address_to_one_chain() {
+        let input: &[u8] = b"0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed\n\
+            1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa\n\
+            nonsense\n";
+        let args = Args::try_parse_from(["blockchain-validator", "-b", "auto", "-f", "-"]).unwrap();
+        let mut tally = Tally::default();
+        let output = output::capture(|| {
+            run_lines(
+                &args,
+                Box::new(input),
+                "-",
+                &selected_chains("auto"),
+                &ValidationOptions::default(),
+                &ProgressBar::hidden(),
+                &mut tally,
+            )
+        });
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines[..3],
+            [
+                format!(
+                    "{} 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed (eth)",
+                    i18n::mark(Mark::Valid)
+                ),
+                format!(
+                    "{} 1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa (btc)",
+                    i18n::mark(Mark::Valid)
+                ),
+                format!(
+                    "{} line 3: nonsense (undetected)",
+                    i18n::mark(Mark::Invalid)
+                ),
+            ]
+        );
+        let per_chain: Vec<(&str, usize)> = tally
+            .per_chain
+            .iter()
+            .map(|(chain, count)| (chain.name, *count))
+            .collect();
+        assert_eq!(per_chain, [("eth", 1), ("btc", 1)]);
+        assert_eq!(tally.undetected, 1);
+    }
+
+    #[test]
+    fn csv_fields_round_trip_with_their_quoting() {
+        // A comma and escaped quotes, and a CRLF inside a quoted field
+        let input: &[u8] = b"address,note\r\n\
+            1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa,\"says \"\"hi\"\", then\r\nleaves\"\r\n\
+            bad,plain\r\n";
+        let chains = [find_chain("btc").unwrap()];
+        let output = output::capture(|| {
+            run_csv(
+                &args(&["--input-format", "csv"]),
+                Box::new(input),
+                "-",
+                &chains,
+                &ValidationOptions::default(),
+                &ProgressBar::hidden(),
+                &mut Tally::default(),
+            )
+        });
+
+        assert_eq!(
+            output,
+            "address,note,valid,matches,line\r\n\
+             1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa,\"says \"\"hi\"\", then\r\nleaves\",true,btc,2\r\n\
+             bad,plain,false,,4\r\n"
+        );
+    }
+
+    fn csv_lines(input: &'static [u8]) -> Vec<String> {
+        let chains = [find_chain("btc").unwrap()];
+        let output = output::capture(|| {
+            run_csv(
+                &args(&["--input-format", "csv"]),
+                Box::new(input),
+                "-",
+                &chains,
+                &ValidationOptions::default(),
+                &ProgressBar::hidden(),
+                &mut Tally::default(),
+            )
+        });
+        csv::Reader::from_reader(output.as_bytes())
+            .records()
+            .map(|record| record.unwrap()[3].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn csv_records_are_numbered_by_line_with_lf_endings() {
+        let input = b"address\n1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa\n\"multi\nline\"\nbad\n";
+        assert_eq!(csv_lines(input), ["2", "3", "5"]);
+    }
+
+    #[test]
+    fn csv_records_are_numbered_by_line_with_mixed_endings() {
+        let input = b"address\r\n1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa\nbad\r\nworse\nlast\r\n";
+        assert_eq!(csv_lines(input), ["2", "3", "4", "5"]);
+    }
+}
@@ -0,0 +1,179 @@
+// Bech32 and Bech32m encoding and decoding (BIP-173, BIP-350)
+use std::fmt;
+
+pub const CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const BECH32_CONST: u32 = 1;
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Bech32,
+    Bech32m,
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Variant::Bech32 => "bech32",
+            Variant::Bech32m => "bech32m",
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Decoded {
+    // Lowercased human-readable part
+    pub hrp: String,
+    // Data part as 5-bit values, without the checksum
+    pub data: Vec<u8>,
+    pub variant: Variant,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    MixedCase,
+    MissingSeparator,
+    EmptyHrp,
+    InvalidChar(char, usize),
+    TooShort,
+    InvalidChecksum,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::MixedCase => write!(f, "mixed upper and lower case"),
+            Error::MissingSeparator => write!(f, "missing '1' separator"),
+            Error::EmptyHrp => write!(f, "empty human-readable part"),
+            Error::InvalidChar(c, i) => write!(f, "invalid character '{}' at position {}", c, i),
+            Error::TooShort => write!(f, "data part too short for a checksum"),
+            Error::InvalidChecksum => write!(f, "invalid checksum"),
+        }
+    }
+}
+
+fn polymod(values: impl Iterator<Item = u8>) -> u32 {
+    const GEN: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+
+    let mut chk: u32 = 1;
+    for value in values {
+        let top = chk >> 25;
+        chk = ((chk & 0x01ff_ffff) << 5) ^ u32::from(value);
+        for (i, gen) in GEN.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= gen;
+            }
+        }
+    }
+    chk
+}
+
+fn hrp_expand(hrp: &str) -> impl Iterator<Item = u8> + '_ {
+    hrp.bytes()
+        .map(|b| b >> 5)
+        .chain(std::iter::once(0))
+        .chain(hrp.bytes().map(|b| b & 0x1f))
+}
+
+// Decode a bech32 or bech32m string, verifying its checksum. The HRP is
+// everything before the last '1'. No overall length limit is applied here,
+// since some formats (e.g. Litecoin MWEB) exceed BIP-173's 90 characters.
+pub fn decode(s: &str) -> Result<Decoded, Error> {
+    let has_lower = s.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = s.chars().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return Err(Error::MixedCase);
+    }
+    let s = s.to_ascii_lowercase();
+
+    let separator = s.rfind('1').ok_or(Error::MissingSeparator)?;
+    let (hrp, data) = (&s[..separator], &s[separator + 1..]);
+    if hrp.is_empty() {
+        return Err(Error::EmptyHrp);
+    }
+    if let Some((i, c)) = hrp
+        .char_indices()
+        .find(|(_, c)| !(33..=126).contains(&(*c as u32)))
+    {
+        return Err(Error::InvalidChar(c, i));
+    }
+
+    let mut values = Vec::with_capacity(data.len());
+    for (i, c) in data.char_indices() {
+        match CHARSET.iter().position(|&b| b as char == c) {
+            Some(value) => values.push(value as u8),
+            None => return Err(Error::InvalidChar(c, separator + 1 + i)),
+        }
+    }
+    if values.len() < 6 {
+        return Err(Error::TooShort);
+    }
+
+    let variant = match polymod(hrp_expand(hrp).chain(values.iter().copied())) {
+        BECH32_CONST => Variant::Bech32,
+        BECH32M_CONST => Variant::Bech32m,
+        _ => return Err(Error::InvalidChecksum),
+    };
+
+    values.truncate(values.len() - 6);
+    Ok(Decoded {
+        hrp: hrp.to_string(),
+        data: values,
+        variant,
+    })
+}
+
+// Encode 5-bit `data` under `hrp` with the checksum of `variant`
+pub fn encode(hrp: &str, data: &[u8], variant: Variant) -> String {
+    let constant = match variant {
+        Variant::Bech32 => BECH32_CONST,
+        Variant::Bech32m => BECH32M_CONST,
+    };
+    let checksum = polymod(hrp_expand(hrp).chain(data.iter().copied()).chain([0; 6])) ^ constant;
+
+    let mut s = format!("{}1", hrp);
+    for &value in data {
+        s.push(CHARSET[usize::from(value)] as char);
+    }
+    for i in 0..6 {
+        s.push(CHARSET[((checksum >> (5 * (5 - i))) & 0x1f) as usize] as char);
+    }
+    s
+}
+
+// Regroup bits, e.g. 5-bit bech32 values into bytes. Without padding,
+// leftover bits must be fewer than `from` and all zero.
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max = (1 << to) - 1;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize + 1);
+
+    for &value in data {
+        if u32::from(value) >> from != 0 {
+            return None;
+        }
+        acc = (acc << from) | u32::from(value);
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max) as u8);
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || (acc << (to - bits)) & max != 0 {
+        return None;
+    }
+    Some(out)
+}
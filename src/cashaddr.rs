@@ -0,0 +1,242 @@
+// Bitcoin Cash CashAddr addresses: an optional `bitcoincash:` prefix, then
+// base32 over a version byte, a hash and a 40-bit BCH checksum that covers
+// the prefix. The version byte's type bits also mark token-aware addresses
+// (CashTokens), which wallets without token support don't recognize.
+// Legacy base58 addresses, shared with Bitcoin, are still accepted.
+use crate::i18n::{self, Msg};
+use crate::{base58check, bech32, CheckCode, Network, ValidationOptions, ValidationResult};
+
+const PREFIXES: &[(&str, Network)] = &[
+    ("bitcoincash", Network::Mainnet),
+    ("bchtest", Network::Testnet),
+    ("bchreg", Network::Testnet),
+];
+
+// Address types by the version byte's type bits (bits 3-6), and whether they
+// are token-aware
+const TYPES: &[(u8, &str, bool)] = &[
+    (0, "P2PKH", false),
+    (1, "P2SH", false),
+    (2, "token-aware P2PKH", true),
+    (3, "token-aware P2SH", true),
+];
+
+// Hash lengths in bytes by the version byte's size bits (bits 0-2)
+const HASH_SIZES: [usize; 8] = [20, 24, 28, 32, 40, 48, 56, 64];
+
+fn polymod(values: impl Iterator<Item = u8>) -> u64 {
+    const GEN: [u64; 5] = [
+        0x98_f2bc_8e61,
+        0x79_b76d_99e2,
+        0xf3_3e5f_b3c4,
+        0xae_2eab_e2a8,
+        0x1e_4f43_e470,
+    ];
+    let mut chk: u64 = 1;
+    for value in values {
+        let top = chk >> 35;
+        chk = ((chk & 0x07_ffff_ffff) << 5) ^ u64::from(value);
+        for (i, gen) in GEN.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= gen;
+            }
+        }
+    }
+    chk ^ 1
+}
+
+// Whether the address is in the legacy base58 format rather than CashAddr
+pub fn is_legacy(address: &str) -> bool {
+    !address.contains(':') && address.starts_with(['1', '3'])
+}
+
+pub fn validate_bch_address(
+    address: &str,
+    options: &ValidationOptions,
+    result: &mut ValidationResult,
+) {
+    let address = address.trim();
+    if is_legacy(address) {
+        return validate_legacy(address, result);
+    }
+
+    let case_ok = address.chars().all(|c| !c.is_ascii_uppercase())
+        || address.chars().all(|c| !c.is_ascii_lowercase());
+    result.add_check(
+        CheckCode::Decoding,
+        "Consistent case",
+        case_ok,
+        if case_ok {
+            "true".to_string()
+        } else {
+            "false (mixed upper and lower case)".to_string()
+        },
+    );
+    let lower = address.to_ascii_lowercase();
+
+    // Without a prefix the address is read as mainnet, as wallets do
+    let (prefix, payload) = lower.split_once(':').unwrap_or(("bitcoincash", &lower));
+    let network = PREFIXES
+        .iter()
+        .find(|(known, _)| *known == prefix)
+        .map(|(_, network)| *network);
+    result.add_check(
+        CheckCode::Prefix,
+        "CashAddr prefix",
+        network.is_some(),
+        match network {
+            Some(_) => format!("'{}'", prefix),
+            None => format!(
+                "unknown prefix '{}' (expected bitcoincash, bchtest or bchreg)",
+                prefix
+            ),
+        },
+    );
+    result.network = network;
+
+    let data: Result<Vec<u8>, (char, usize)> = payload
+        .char_indices()
+        .map(|(i, c)| {
+            bech32::CHARSET
+                .iter()
+                .position(|&b| b as char == c)
+                .map(|value| value as u8)
+                .ok_or((c, i))
+        })
+        .collect();
+    let data = match data {
+        Ok(data) => data,
+        Err((c, i)) => {
+            let offset = address.len() - payload.len();
+            result.add_check(
+                CheckCode::Base32Characters,
+                "Base32 characters",
+                false,
+                format!("invalid character '{}' at position {}", c, offset + i),
+            );
+            return;
+        }
+    };
+    result.add_check(
+        CheckCode::Base32Characters,
+        "Base32 characters",
+        true,
+        "true".to_string(),
+    );
+
+    let checksum_ok = data.len() > 8
+        && polymod(
+            prefix
+                .bytes()
+                .map(|b| b & 0x1f)
+                .chain([0])
+                .chain(data.iter().copied()),
+        ) == 0;
+    result.add_check(
+        CheckCode::Checksum,
+        "CashAddr checksum",
+        checksum_ok,
+        format!("{}", checksum_ok),
+    );
+    if (!result.valid && !options.all_checks) || data.len() <= 8 {
+        return;
+    }
+
+    let Some(bytes) = bech32::convert_bits(&data[..data.len() - 8], 5, 8, false) else {
+        result.add_check(
+            CheckCode::Decoding,
+            "CashAddr payload",
+            false,
+            "leftover bits after the last byte".to_string(),
+        );
+        return;
+    };
+    let Some((&version, hash)) = bytes.split_first() else {
+        result.add_check(
+            CheckCode::Decoding,
+            "CashAddr payload",
+            false,
+            "empty payload".to_string(),
+        );
+        return;
+    };
+
+    let kind = TYPES
+        .iter()
+        .find(|(bits, _, _)| *bits == (version >> 3) & 0x0f)
+        .filter(|_| version & 0x80 == 0);
+    result.add_check(
+        CheckCode::AddressType,
+        "Address type",
+        kind.is_some(),
+        match kind {
+            Some((_, name, _)) => name.to_string(),
+            None => format!("unknown address type (version byte 0x{:02x})", version),
+        },
+    );
+
+    let expected = HASH_SIZES[usize::from(version & 0x07)];
+    let length_ok = hash.len() == expected;
+    result.add_check(
+        CheckCode::DecodedLength,
+        &format!("Hash length ({} bytes)", expected),
+        length_ok,
+        i18n::fill(Msg::Actual, &[&length_ok, &hash.len()]),
+    );
+    result.payload = Some(hash.to_vec());
+
+    match kind {
+        Some((_, _, true)) if result.valid => {
+            result.add_warning(i18n::text(Msg::TokenAware).to_string())
+        }
+        Some((_, _, false)) if result.valid => result
+            .notes
+            .push(i18n::text(Msg::NotTokenAware).to_string()),
+        _ => {}
+    }
+}
+
+// Base58check with Bitcoin's version bytes and checksum
+fn validate_legacy(address: &str, result: &mut ValidationResult) {
+    let payload = base58check::decode(address, base58check::double_sha256);
+    result.add_check(
+        CheckCode::Checksum,
+        "Base58Check checksum",
+        payload.is_ok(),
+        match &payload {
+            Ok(_) => "true".to_string(),
+            Err(err) => err.to_string(),
+        },
+    );
+    let Ok(payload) = payload else {
+        return;
+    };
+
+    let kind = match payload.first() {
+        Some(0x00) => Some("P2PKH (legacy format)"),
+        Some(0x05) => Some("P2SH (legacy format)"),
+        _ => None,
+    };
+    result.add_check(
+        CheckCode::AddressType,
+        "Address type",
+        kind.is_some(),
+        match kind {
+            Some(name) => name.to_string(),
+            None => format!("unknown version byte 0x{:02x}", payload[0]),
+        },
+    );
+
+    let length_ok = payload.len() == 21;
+    result.add_check(
+        CheckCode::DecodedLength,
+        "Decoded length (21 bytes)",
+        length_ok,
+        i18n::fill(Msg::Actual, &[&length_ok, &payload.len()]),
+    );
+    result.payload = payload.get(1..).map(<[u8]>::to_vec);
+    if result.valid {
+        result.network = Some(Network::Mainnet);
+        result.add_warning(i18n::text(Msg::LegacyCash).to_string());
+    }
+}
@@ -0,0 +1,69 @@
+// Decred addresses: base58check with a two-byte network/type prefix and a
+// double BLAKE-256 checksum
+use crate::i18n::{self, Msg};
+use crate::{base58check, CheckCode, ValidationResult};
+
+// Mainnet version prefixes of hash-based addresses, each followed by a
+// 20-byte hash
+const VERSIONS: &[([u8; 2], &str)] = &[
+    ([0x07, 0x3f], "P2PKH secp256k1 (Ds)"),
+    ([0x07, 0x1f], "P2PKH Ed25519 (De)"),
+    ([0x07, 0x01], "P2PKH Schnorr secp256k1 (DS)"),
+    ([0x07, 0x1a], "P2SH (Dc)"),
+];
+
+pub fn validate_dcr_address(address: &str, result: &mut ValidationResult) {
+    let prefix_ok = address.starts_with('D');
+    result.add_check(
+        CheckCode::Prefix,
+        "Starts with D",
+        prefix_ok,
+        format!("{}", prefix_ok),
+    );
+
+    let payload = match base58check::decode(address, base58check::double_blake256) {
+        Ok(payload) => {
+            result.add_check(
+                CheckCode::Checksum,
+                "Base58Check checksum (BLAKE-256)",
+                true,
+                "true".to_string(),
+            );
+            payload
+        }
+        Err(err) => {
+            result.add_check(
+                CheckCode::Checksum,
+                "Base58Check checksum (BLAKE-256)",
+                false,
+                err.to_string(),
+            );
+            return;
+        }
+    };
+
+    let kind = VERSIONS
+        .iter()
+        .find(|(version, _)| payload.starts_with(version));
+    result.add_check(
+        CheckCode::AddressType,
+        "Address type",
+        kind.is_some(),
+        match kind {
+            Some((_, name)) => name.to_string(),
+            None => format!(
+                "unknown version prefix {}",
+                hex::encode(&payload[..payload.len().min(2)])
+            ),
+        },
+    );
+
+    let length_ok = payload.len() == 22;
+    result.add_check(
+        CheckCode::DecodedLength,
+        "Decoded length (22 bytes)",
+        length_ok,
+        i18n::fill(Msg::Actual, &[&length_ok, &payload.len()]),
+    );
+    result.payload = payload.get(2..).map(<[u8]>::to_vec);
+}
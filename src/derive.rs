@@ -0,0 +1,334 @@
+// BIP-32 child key derivation from an extended key, used to check that a
+// wallet's key and path produce the address it should. Public keys can only
+// derive non-hardened children; hardened steps need the private key.
+use crate::{base58check, bech32, to_checksum_address, xpub, BtcAddressType, Chain, Format};
+use hmac::{Hmac, Mac};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::PrimeField;
+use k256::{FieldBytes, ProjectivePoint, PublicKey, Scalar};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256, Sha512};
+use sha3::Keccak256;
+
+const HARDENED: u32 = 1 << 31;
+
+enum Key {
+    Private(Scalar),
+    Public(ProjectivePoint),
+}
+
+struct ExtendedKey {
+    // Key type name from the version bytes, e.g. "xpub (P2PKH or P2SH, mainnet)"
+    kind: &'static str,
+    chain_code: [u8; 32],
+    key: Key,
+}
+
+impl ExtendedKey {
+    fn parse(s: &str) -> Result<Self, String> {
+        let payload = base58check::decode(s.trim(), base58check::double_sha256)
+            .map_err(|err| format!("invalid extended key: {}", err))?;
+        if payload.len() != 78 {
+            return Err(format!(
+                "invalid extended key: {} bytes instead of 78",
+                payload.len()
+            ));
+        }
+        let kind = xpub::key_type(&payload).ok_or_else(|| {
+            format!(
+                "unknown extended key version {}",
+                hex::encode(&payload[..4])
+            )
+        })?;
+
+        let mut chain_code = [0; 32];
+        chain_code.copy_from_slice(&payload[13..45]);
+        let key = if xpub::is_private(kind) {
+            let scalar = scalar(&payload[46..78]).ok_or("private key is out of range")?;
+            Key::Private(scalar)
+        } else {
+            let point = PublicKey::from_sec1_bytes(&payload[45..78])
+                .map_err(|_| "public key is not a valid curve point")?;
+            Key::Public(point.to_projective())
+        };
+        Ok(Self {
+            kind,
+            chain_code,
+            key,
+        })
+    }
+
+    fn point(&self) -> ProjectivePoint {
+        match self.key {
+            Key::Private(scalar) => ProjectivePoint::GENERATOR * scalar,
+            Key::Public(point) => point,
+        }
+    }
+
+    fn compressed_public_key(&self) -> Vec<u8> {
+        self.point()
+            .to_affine()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()
+    }
+
+    // CKDpriv / CKDpub from BIP-32
+    fn child(&self, index: u32) -> Result<Self, String> {
+        let mut mac =
+            Hmac::<Sha512>::new_from_slice(&self.chain_code).expect("HMAC accepts any key length");
+        match self.key {
+            Key::Private(scalar) if index >= HARDENED => {
+                mac.update(&[0]);
+                mac.update(&scalar.to_repr());
+            }
+            Key::Public(_) if index >= HARDENED => {
+                return Err(format!(
+                    "hardened step {}' needs an extended private key",
+                    index - HARDENED
+                ));
+            }
+            _ => mac.update(&self.compressed_public_key()),
+        }
+        mac.update(&index.to_be_bytes());
+        let output = mac.finalize().into_bytes();
+
+        // An out-of-range tweak or a zero key means the index is skipped
+        let invalid = || format!("child {} is invalid; use the next index", index);
+        let tweak = scalar(&output[..32]).ok_or_else(invalid)?;
+        let key = match self.key {
+            Key::Private(scalar) => {
+                let child = tweak + scalar;
+                if bool::from(child.is_zero()) {
+                    return Err(invalid());
+                }
+                Key::Private(child)
+            }
+            Key::Public(point) => {
+                let child = ProjectivePoint::GENERATOR * tweak + point;
+                if child == ProjectivePoint::IDENTITY {
+                    return Err(invalid());
+                }
+                Key::Public(child)
+            }
+        };
+
+        let mut chain_code = [0; 32];
+        chain_code.copy_from_slice(&output[32..]);
+        Ok(Self {
+            kind: self.kind,
+            chain_code,
+            key,
+        })
+    }
+}
+
+fn scalar(bytes: &[u8]) -> Option<Scalar> {
+    Scalar::from_repr(*FieldBytes::from_slice(bytes)).into()
+}
+
+// Parse a path such as m/44'/60'/0'/0/0; `'` or `h` marks a hardened step
+fn parse_path(path: &str) -> Result<Vec<u32>, String> {
+    let mut steps = path.trim().split('/');
+    if steps.next() != Some("m") {
+        return Err("a derivation path starts with m".to_string());
+    }
+    steps
+        .map(|step| {
+            let (number, hardened) = match step.strip_suffix(['\'', 'h']) {
+                Some(number) => (number, true),
+                None => (step, false),
+            };
+            match number.parse::<u32>() {
+                Ok(index) if index < HARDENED => {
+                    Ok(if hardened { index + HARDENED } else { index })
+                }
+                _ => Err(format!("invalid path step '{}'", step)),
+            }
+        })
+        .collect()
+}
+
+fn hash160(data: &[u8]) -> Vec<u8> {
+    Ripemd160::digest(Sha256::digest(data)).to_vec()
+}
+
+// Derive `path` from `key` and encode the resulting public key as an address
+// of `chain`. Bitcoin addresses follow the key's version: P2PKH for xpub,
+// P2SH-wrapped P2WPKH for ypub and native P2WPKH for zpub.
+pub fn derive_address(key: &str, path: &str, chain: &Chain) -> Result<String, String> {
+    let mut key = ExtendedKey::parse(key)?;
+    for index in parse_path(path)? {
+        key = key.child(index)?;
+    }
+
+    match chain.format {
+        Format::Eth {
+            chain_id,
+            address_bytes: 20,
+            eip1191,
+        } => {
+            let uncompressed = key.point().to_affine().to_encoded_point(false);
+            let hash = Keccak256::digest(&uncompressed.as_bytes()[1..]);
+            Ok(to_checksum_address(
+                &hex::encode(&hash[12..]),
+                chain_id.filter(|_| eip1191),
+            ))
+        }
+        Format::Btc(_) if chain.name == "btc" => {
+            let testnet = key.kind.contains("testnet");
+            let pubkey_hash = hash160(&key.compressed_public_key());
+            match key.kind.chars().next() {
+                Some('x' | 't') => {
+                    let version = if testnet { 0x6f } else { 0x00 };
+                    Ok(base58check::encode(
+                        &[&[version], &pubkey_hash[..]].concat(),
+                        base58check::double_sha256,
+                    ))
+                }
+                Some('y' | 'u') => {
+                    let redeem_script = [&[0x00, 0x14], &pubkey_hash[..]].concat();
+                    let version = if testnet { 0xc4 } else { 0x05 };
+                    Ok(base58check::encode(
+                        &[&[version], &hash160(&redeem_script)[..]].concat(),
+                        base58check::double_sha256,
+                    ))
+                }
+                Some('z' | 'v') => {
+                    let program = bech32::convert_bits(&pubkey_hash, 8, 5, true)
+                        .expect("bytes regroup into 5-bit values");
+                    Ok(bech32::encode(
+                        if testnet { "tb" } else { "bc" },
+                        &[&[0], &program[..]].concat(),
+                        bech32::Variant::Bech32,
+                    ))
+                }
+                _ => Err(format!(
+                    "{} keys don't map to a single address type",
+                    key.kind
+                )),
+            }
+        }
+        _ => Err(format!(
+            "derivation is only supported for btc and Ethereum-format chains, not {}",
+            chain.name
+        )),
+    }
+}
+
+// The script through which `key` pays to an address of this type, given the
+// address's hash or witness program. Segwit scripts only take compressed
+// keys, and a taproot address is matched on its key path with no script
+// tree, as BIP-86 wallets derive it.
+pub fn pubkey_script(
+    key: &PublicKey,
+    address_type: BtcAddressType,
+    payload: &[u8],
+) -> Option<&'static str> {
+    let compressed = key.to_encoded_point(true);
+    let compressed = compressed.as_bytes();
+    let uncompressed = key.to_encoded_point(false);
+    let uncompressed = uncompressed.as_bytes();
+    // <key> OP_CHECKSIG
+    let p2pk = |key: &[u8]| [&[key.len() as u8], key, &[0xac]].concat();
+
+    let candidates = match address_type {
+        BtcAddressType::P2pkh => vec![
+            ("P2PKH", hash160(compressed)),
+            ("P2PKH with the uncompressed key", hash160(uncompressed)),
+        ],
+        BtcAddressType::P2sh => vec![
+            (
+                "P2SH-wrapped P2WPKH",
+                hash160(&[&[0x00, 0x14], &hash160(compressed)[..]].concat()),
+            ),
+            ("P2SH-wrapped P2PK", hash160(&p2pk(compressed))),
+            (
+                "P2SH-wrapped P2PK with the uncompressed key",
+                hash160(&p2pk(uncompressed)),
+            ),
+        ],
+        BtcAddressType::P2wpkh => vec![("P2WPKH", hash160(compressed))],
+        BtcAddressType::P2wsh => vec![(
+            "P2WSH-wrapped P2PK",
+            Sha256::digest(p2pk(compressed)).to_vec(),
+        )],
+        BtcAddressType::P2tr => vec![("P2TR key path", taproot_output_key(key))],
+        BtcAddressType::P2a | BtcAddressType::FutureSegwit(_) => Vec::new(),
+    };
+    candidates
+        .into_iter()
+        .find(|(_, hash)| hash == payload)
+        .map(|(script, _)| script)
+}
+
+// BIP-341's output key for an internal key with no script tree: the x
+// coordinate of P + hash_TapTweak(x(P))G, where P has the key's x and an
+// even y
+fn taproot_output_key(key: &PublicKey) -> Vec<u8> {
+    let compressed = key.to_encoded_point(true);
+    let x = &compressed.as_bytes()[1..];
+    let even = PublicKey::from_sec1_bytes(&[&[0x02], x].concat()).expect("x is on the curve");
+    let tag = Sha256::digest(b"TapTweak");
+    let tweak = Sha256::new()
+        .chain_update(tag)
+        .chain_update(tag)
+        .chain_update(x)
+        .finalize();
+    let Some(tweak) = scalar(&tweak) else {
+        return Vec::new();
+    };
+    let output = even.to_projective() + ProjectivePoint::GENERATOR * tweak;
+    output.to_affine().to_encoded_point(true).as_bytes()[1..].to_vec()
+}
+
+// `name` as exactly `len` bytes of hex, with or without 0x
+fn parse_hex(name: &str, value: &str, len: usize) -> Result<Vec<u8>, String> {
+    let digits = value.trim();
+    let digits = digits.strip_prefix("0x").unwrap_or(digits);
+    let bytes = hex::decode(digits).map_err(|err| format!("{} is not hex: {}", name, err))?;
+    if bytes.len() != len {
+        return Err(format!(
+            "{} must be {} bytes (got {})",
+            name,
+            len,
+            bytes.len()
+        ));
+    }
+    Ok(bytes)
+}
+
+// The address a contract deployed with CREATE2 gets (EIP-1014):
+// keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))[12..]
+pub fn create2_address(
+    deployer: &str,
+    salt: &str,
+    init_code_hash: &str,
+    chain: &Chain,
+) -> Result<String, String> {
+    let Format::Eth {
+        chain_id,
+        address_bytes: 20,
+        eip1191,
+    } = chain.format
+    else {
+        return Err(format!(
+            "CREATE2 addresses are only defined for Ethereum-format chains, not {}",
+            chain.name
+        ));
+    };
+    let deployer = parse_hex("--deployer", deployer, 20)?;
+    let salt = parse_hex("--salt", salt, 32)?;
+    let init_code_hash = parse_hex("--init-code-hash", init_code_hash, 32)?;
+
+    let hash = Keccak256::new()
+        .chain_update([0xff])
+        .chain_update(deployer)
+        .chain_update(salt)
+        .chain_update(init_code_hash)
+        .finalize();
+    Ok(to_checksum_address(
+        &hex::encode(&hash[12..]),
+        chain_id.filter(|_| eip1191),
+    ))
+}
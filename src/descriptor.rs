@@ -0,0 +1,131 @@
+// Output descriptors (BIP-380 and later), such as
+// `wpkh([d34db33f/84h/0h/0h]xpub.../0/*)#cjjspncu`. They describe a wallet's
+// scripts rather than pay to one address, and end in an optional 8-character
+// checksum over the descriptor text.
+use crate::{CheckCode, ValidationResult};
+
+// The script expressions a descriptor can start with
+const FUNCTIONS: &[&str] = &[
+    "sh",
+    "wsh",
+    "pk",
+    "pkh",
+    "wpkh",
+    "combo",
+    "multi",
+    "sortedmulti",
+    "multi_a",
+    "sortedmulti_a",
+    "tr",
+    "addr",
+    "raw",
+    "rawtr",
+];
+
+// Characters grouped so that the checksum catches the most likely typos:
+// the position within its group of 32 and the group number are hashed
+// separately
+const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}\
+IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~\
+ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+// The outermost function of a descriptor, if the input is one
+pub fn function(input: &str) -> Option<&'static str> {
+    let (name, rest) = input.split_once('(')?;
+    let body = rest.split_once('#').map_or(rest, |(body, _)| body);
+    if !body.ends_with(')') {
+        return None;
+    }
+    FUNCTIONS
+        .iter()
+        .find(|function| **function == name)
+        .copied()
+}
+
+fn polymod(chk: u64, value: u64) -> u64 {
+    const GEN: [u64; 5] = [
+        0xf5_dee5_1989,
+        0xa9_fdca_3312,
+        0x1b_ab10_e32d,
+        0x37_06b1_677a,
+        0x64_4d62_6ffd,
+    ];
+    let top = chk >> 35;
+    let mut chk = ((chk & 0x07_ffff_ffff) << 5) ^ value;
+    for (i, gen) in GEN.iter().enumerate() {
+        if (top >> i) & 1 == 1 {
+            chk ^= gen;
+        }
+    }
+    chk
+}
+
+// The checksum of a descriptor without its `#` suffix, or the first
+// character that descriptors can't contain
+fn checksum(descriptor: &str) -> Result<String, (char, usize)> {
+    let mut chk = 1;
+    let mut groups = 0;
+    let mut count = 0;
+    for (i, c) in descriptor.char_indices() {
+        let position = INPUT_CHARSET.find(c).ok_or((c, i))? as u64;
+        chk = polymod(chk, position & 31);
+        groups = groups * 3 + (position >> 5);
+        count += 1;
+        if count == 3 {
+            chk = polymod(chk, groups);
+            groups = 0;
+            count = 0;
+        }
+    }
+    if count > 0 {
+        chk = polymod(chk, groups);
+    }
+    for _ in 0..8 {
+        chk = polymod(chk, 0);
+    }
+    chk ^= 1;
+    Ok((0..8)
+        .map(|i| CHECKSUM_CHARSET[((chk >> (5 * (7 - i))) & 31) as usize] as char)
+        .collect())
+}
+
+pub fn validate_descriptor(input: &str, function: &str, result: &mut ValidationResult) {
+    result.add_check(
+        CheckCode::AddressType,
+        "Address type",
+        false,
+        format!("output descriptor ({}(...)), not an address", function),
+    );
+
+    let (descriptor, given) = match input.split_once('#') {
+        Some((descriptor, given)) => (descriptor, Some(given)),
+        None => (input, None),
+    };
+    match (checksum(descriptor), given) {
+        (Err((c, i)), _) => result.add_check(
+            CheckCode::Checksum,
+            "Descriptor checksum",
+            false,
+            format!("invalid character '{}' at position {}", c, i),
+        ),
+        (Ok(expected), Some(given)) if expected == given => result.add_check(
+            CheckCode::Checksum,
+            "Descriptor checksum",
+            true,
+            "true".to_string(),
+        ),
+        (Ok(expected), Some(given)) => result.add_check(
+            CheckCode::Checksum,
+            "Descriptor checksum",
+            false,
+            format!("false (given #{}, expected #{})", given, expected),
+        ),
+        (Ok(expected), None) => result.add_check(
+            CheckCode::Checksum,
+            "Descriptor checksum",
+            true,
+            format!("none given (it would be #{})", expected),
+        ),
+    }
+}
@@ -0,0 +1,70 @@
+// EIP-681 transaction request URIs:
+// `ethereum:[pay-]<target>[@<chain id>][/<function>][?<parameters>]`. The
+// target is an address or ENS name; a token transfer names its recipient in
+// an `address` parameter.
+
+pub const SCHEME: &str = "ethereum:";
+
+#[derive(Debug)]
+pub struct PaymentRequest<'a> {
+    pub target: &'a str,
+    // The network the request is for; absent means the wallet's current one
+    pub chain_id: Option<u64>,
+    pub function: Option<&'a str>,
+    pub address_parameter: Option<&'a str>,
+}
+
+// Whether the input is an `ethereum:` URI rather than a bare address
+pub fn is_uri(input: &str) -> bool {
+    input
+        .get(..SCHEME.len())
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case(SCHEME))
+}
+
+pub fn parse(uri: &str) -> Result<PaymentRequest<'_>, String> {
+    let rest = uri
+        .trim()
+        .get(SCHEME.len()..)
+        .filter(|_| is_uri(uri.trim()))
+        .ok_or_else(|| format!("expected a URI starting with '{}'", SCHEME))?;
+    let rest = rest.strip_prefix("pay-").unwrap_or(rest);
+
+    let (rest, query) = match rest.split_once('?') {
+        Some((rest, query)) => (rest, Some(query)),
+        None => (rest, None),
+    };
+    let (rest, function) = match rest.split_once('/') {
+        Some((rest, function)) => (rest, Some(function)),
+        None => (rest, None),
+    };
+    let (target, chain_id) = match rest.split_once('@') {
+        Some((target, chain_id)) => {
+            let chain_id = chain_id
+                .parse()
+                .map_err(|_| format!("invalid chain id '{}'", chain_id))?;
+            (target, Some(chain_id))
+        }
+        None => (rest, None),
+    };
+    if target.is_empty() {
+        return Err("no target address".to_string());
+    }
+    if function.is_some_and(str::is_empty) {
+        return Err("empty function name".to_string());
+    }
+
+    let address_parameter = query.and_then(|query| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == "address")
+            .map(|(_, value)| value)
+    });
+
+    Ok(PaymentRequest {
+        target,
+        chain_id,
+        function,
+        address_parameter,
+    })
+}
@@ -0,0 +1,96 @@
+// ENS names such as `vitalik.eth`. Only the shape of the name is checked;
+// whether it is registered or resolves needs a network lookup.
+use crate::i18n::{self, Msg};
+use crate::{CheckCode, ValidationResult};
+
+// Top-level domains whose names can resolve through ENS: the native .eth
+// registry plus common DNS TLDs imported via DNSSEC
+pub const TLDS: &[&str] = &[
+    "eth", "xyz", "com", "org", "io", "app", "art", "club", "kred", "luxe",
+];
+
+// Whether `name` has the shape of an ENS name rather than a raw address:
+// it contains a dot, ends in a known TLD and isn't 0x hex
+pub fn looks_like_ens_name(name: &str) -> bool {
+    let name = name.trim();
+    if name.starts_with("0x") || name.starts_with("0X") {
+        return false;
+    }
+    match name.rsplit_once('.') {
+        Some((rest, tld)) => !rest.is_empty() && TLDS.contains(&tld.to_lowercase().as_str()),
+        None => false,
+    }
+}
+
+pub fn validate_ens_name(name: &str, result: &mut ValidationResult) {
+    let labels: Vec<&str> = name.split('.').collect();
+
+    let tld = labels.last().copied().unwrap_or_default();
+    let tld_ok = labels.len() > 1 && TLDS.contains(&tld.to_lowercase().as_str());
+    result.add_check(
+        CheckCode::Tld,
+        "Known TLD",
+        tld_ok,
+        if tld_ok {
+            format!("true (.{})", tld)
+        } else {
+            format!("'{}' is not a known ENS TLD", tld)
+        },
+    );
+
+    let empty_labels = labels.iter().filter(|label| label.is_empty()).count();
+    result.add_check(
+        CheckCode::Separators,
+        "No empty labels",
+        empty_labels == 0,
+        if empty_labels == 0 {
+            "true".to_string()
+        } else {
+            format!("{} empty label(s)", empty_labels)
+        },
+    );
+
+    // Full ENSIP-15 normalization covers Unicode; non-ASCII labels are
+    // passed through with a warning rather than judged here
+    let invalid = name
+        .chars()
+        .find(|&c| c.is_ascii() && !(c.is_ascii_alphanumeric() || c == '-' || c == '.'));
+    result.add_check(
+        CheckCode::NameCharacters,
+        "Valid name characters",
+        invalid.is_none(),
+        match invalid {
+            None => "true".to_string(),
+            Some(c) => format!("invalid character '{}'", c),
+        },
+    );
+    if !name.is_ascii() {
+        result.add_warning(i18n::text(Msg::EnsNonAscii).to_string());
+    }
+
+    let normalized = name.to_lowercase();
+    let lowercase_ok = normalized == name;
+    result.add_check(
+        CheckCode::NameCharacters,
+        "Lowercase (normalized)",
+        lowercase_ok,
+        if lowercase_ok {
+            "true".to_string()
+        } else {
+            format!("false (normalized: {})", normalized)
+        },
+    );
+
+    // The .eth registrar only issues second-level names of 3+ characters
+    if tld.eq_ignore_ascii_case("eth") && labels.len() > 1 {
+        let second_level = labels[labels.len() - 2];
+        let length = second_level.chars().count();
+        let length_ok = length >= 3;
+        result.add_check(
+            CheckCode::Length,
+            "Second-level name (at least 3 chars)",
+            length_ok,
+            i18n::fill(Msg::Actual, &[&length_ok, &length]),
+        );
+    }
+}
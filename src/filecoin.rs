@@ -0,0 +1,204 @@
+// Filecoin addresses: a network letter (f mainnet, t testnet), a protocol
+// digit and a protocol-specific payload. Every protocol but ID ends in a
+// 4-byte Blake2b checksum over the protocol byte and payload, base32-encoded
+// together with the payload.
+use crate::base32;
+use crate::i18n::{self, Msg};
+use crate::{CheckCode, Network, ValidationOptions, ValidationResult};
+use blake2::digest::{Update, VariableOutput};
+use blake2::Blake2bVar;
+
+const PROTOCOLS: &[&str] = &[
+    "ID (f0)",
+    "secp256k1 (f1)",
+    "Actor (f2)",
+    "BLS (f3)",
+    "Delegated (f4)",
+];
+
+// Delegated addresses carry at most 54 bytes of sub-address
+const MAX_SUBADDRESS_BYTES: usize = 54;
+
+pub fn validate_fil_address(
+    address: &str,
+    options: &ValidationOptions,
+    result: &mut ValidationResult,
+) {
+    let network = match address.chars().next() {
+        Some('f') => Some(Network::Mainnet),
+        Some('t') => Some(Network::Testnet),
+        _ => None,
+    };
+    result.add_check(
+        CheckCode::Prefix,
+        "Network prefix (f or t)",
+        network.is_some(),
+        format!("{}", network.is_some()),
+    );
+    result.network = network;
+
+    let protocol = address
+        .chars()
+        .nth(1)
+        .and_then(|c| c.to_digit(10))
+        .map(|p| p as usize)
+        .filter(|&p| p < PROTOCOLS.len());
+    result.add_check(
+        CheckCode::AddressType,
+        "Protocol",
+        protocol.is_some(),
+        match protocol {
+            Some(p) => PROTOCOLS[p].to_string(),
+            None => "unknown protocol (expected 0-4)".to_string(),
+        },
+    );
+    let Some(protocol) = protocol.filter(|_| result.valid || options.all_checks) else {
+        return;
+    };
+
+    // The first character is only known to be ASCII when the prefix passed
+    let rest = address
+        .char_indices()
+        .nth(2)
+        .map_or("", |(i, _)| &address[i..]);
+    match protocol {
+        0 => {
+            let id_ok = parse_id(rest).is_some();
+            result.add_check(
+                CheckCode::Decoding,
+                "Actor ID (unsigned 64-bit)",
+                id_ok,
+                format!("{}", id_ok),
+            );
+            result.payload = parse_id(rest).map(leb128);
+        }
+        4 => validate_delegated(rest, result),
+        _ => {
+            let expected = if protocol == 3 { 48 } else { 20 };
+            validate_payload(
+                protocol as u8,
+                &[],
+                rest,
+                2,
+                expected..=expected,
+                result,
+            );
+        }
+    }
+}
+
+// f4<namespace>f<base32 sub-address and checksum>
+fn validate_delegated(rest: &str, result: &mut ValidationResult) {
+    let parts = rest.split_once('f');
+    let namespace = parts.and_then(|(namespace, _)| parse_id(namespace));
+    result.add_check(
+        CheckCode::Decoding,
+        "Namespace actor ID",
+        namespace.is_some(),
+        match namespace {
+            Some(namespace) => format!("true ({})", namespace),
+            None => "expected f4<actor id>f<sub-address>".to_string(),
+        },
+    );
+    if let (Some((namespace_str, encoded)), Some(namespace)) = (parts, namespace) {
+        validate_payload(
+            4,
+            &leb128(namespace),
+            encoded,
+            3 + namespace_str.len(),
+            0..=MAX_SUBADDRESS_BYTES,
+            result,
+        );
+    }
+}
+
+// Decode a base32 payload plus checksum, which starts at `offset` in the
+// address, and check the payload length and checksum
+fn validate_payload(
+    protocol: u8,
+    prefix: &[u8],
+    encoded: &str,
+    offset: usize,
+    length: std::ops::RangeInclusive<usize>,
+    result: &mut ValidationResult,
+) {
+    let bytes = match base32::decode(encoded, base32::RFC4648_LOWER, false) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            result.add_check(
+                CheckCode::Base32Characters,
+                "Base32 characters",
+                false,
+                match err {
+                    base32::Error::InvalidChar(c, i) => {
+                        format!("invalid character '{}' at position {}", c, offset + i)
+                    }
+                    err => err.to_string(),
+                },
+            );
+            return;
+        }
+    };
+    result.add_check(
+        CheckCode::Base32Characters,
+        "Base32 characters",
+        true,
+        "true".to_string(),
+    );
+
+    let payload_len = bytes.len().saturating_sub(4);
+    let length_ok = bytes.len() >= 4 && length.contains(&payload_len);
+    result.add_check(
+        CheckCode::DecodedLength,
+        &if length.start() == length.end() {
+            format!("Payload length ({} bytes)", length.end())
+        } else {
+            format!("Payload length (at most {} bytes)", length.end())
+        },
+        length_ok,
+        i18n::fill(Msg::Actual, &[&length_ok, &payload_len]),
+    );
+    if !length_ok {
+        return;
+    }
+
+    let (payload, checksum) = bytes.split_at(payload_len);
+    let mut hasher = Blake2bVar::new(4).expect("4 is a valid Blake2b output size");
+    hasher.update(&[protocol]);
+    hasher.update(prefix);
+    hasher.update(payload);
+    let mut expected = [0; 4];
+    hasher
+        .finalize_variable(&mut expected)
+        .expect("output buffer matches the requested size");
+    let checksum_ok = checksum == expected;
+    result.add_check(
+        CheckCode::Checksum,
+        "Blake2b checksum",
+        checksum_ok,
+        format!("{}", checksum_ok),
+    );
+    result.payload = Some(payload.to_vec());
+}
+
+// A decimal u64 without leading zeros
+fn parse_id(s: &str) -> Option<u64> {
+    let digits = !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !digits || (s.len() > 1 && s.starts_with('0')) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+fn leb128(mut value: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return out;
+        }
+        out.push(byte | 0x80);
+    }
+}
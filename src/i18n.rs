@@ -0,0 +1,490 @@
+// Translations of the human-readable text output. JSON and other structured
+// output, RESULT lines and check codes stay in English so scripts see the
+// same text everywhere.
+use clap::ValueEnum;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Lang {
+    En,
+    De,
+}
+
+impl Lang {
+    // The language named by a locale such as `de_DE.UTF-8`, if supported
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        match locale.get(..2)? {
+            "en" => Some(Lang::En),
+            "de" => Some(Lang::De),
+            _ => None,
+        }
+    }
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+static ASCII: OnceLock<bool> = OnceLock::new();
+
+// Tests pick a language for their thread without touching the global one
+#[cfg(test)]
+thread_local! {
+    static TEST_LANG: std::cell::Cell<Option<Lang>> = const { std::cell::Cell::new(None) };
+}
+
+pub fn set_lang(lang: Lang) {
+    let _ = LANG.set(lang);
+}
+
+fn lang() -> Lang {
+    #[cfg(test)]
+    if let Some(lang) = TEST_LANG.with(|lang| lang.get()) {
+        return lang;
+    }
+    *LANG.get().unwrap_or(&Lang::En)
+}
+
+// What `f` returns with the text in `lang`, as a test sees it
+#[cfg(test)]
+pub fn with_lang<T>(lang: Lang, f: impl FnOnce() -> T) -> T {
+    TEST_LANG.with(|test_lang| test_lang.set(Some(lang)));
+    let value = f();
+    TEST_LANG.with(|test_lang| test_lang.set(None));
+    value
+}
+
+// Use plain text markers instead of emoji, for terminals that can't render them
+pub fn set_ascii(ascii: bool) {
+    let _ = ASCII.set(ascii);
+}
+
+// The result markers that start output lines
+#[derive(Debug, Clone, Copy)]
+pub enum Mark {
+    Valid,
+    Invalid,
+    Warning,
+    // Single checks in verbose details
+    Passed,
+    Failed,
+}
+
+pub fn mark(mark: Mark) -> &'static str {
+    match (*ASCII.get().unwrap_or(&false), mark) {
+        (false, Mark::Valid) => "✅",
+        (false, Mark::Invalid) => "❌",
+        // The emoji renders two columns wide, so it gets a trailing space
+        (false, Mark::Warning) => "⚠️ ",
+        (false, Mark::Passed) => "✓",
+        (false, Mark::Failed) => "✗",
+        (true, Mark::Valid | Mark::Passed) => "[PASS]",
+        (true, Mark::Invalid | Mark::Failed) => "[FAIL]",
+        (true, Mark::Warning) => "[WARN]",
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Msg {
+    Valid,
+    Invalid,
+    Details,
+    ChainDetails,
+    ValidFor,
+    ValidForNone,
+    Summary,
+    Example,
+    DetectedEns,
+    DetectedRaw,
+    ChecksumDiff,
+    DiffGiven,
+    DiffExpected,
+    // Check messages
+    Actual,
+    ActualVersion,
+    SkippedLowercase,
+    OffBy,
+    TooShort,
+    // Warnings and notes
+    AllLowercase,
+    RemovedAnnotation,
+    NetworkMismatch,
+    NearMissShort,
+    NearMissOver,
+    Taproot,
+    ScriptHash,
+    DustLimit,
+    ForeignHrp,
+    NoSingleFix,
+    LikelyTypo,
+    SeveralFixes,
+    OnCurve,
+    OffCurve,
+    ShortMove,
+    PolicyRejected,
+    PolicyAddressType,
+    PolicyNetwork,
+    PrivateKey,
+    TokenAware,
+    NotTokenAware,
+    LegacyCash,
+    EnsNonAscii,
+    Note,
+    // --compare-chains
+    RiskSameFamily,
+    RiskUnrelated,
+    And,
+    NoOverlap,
+    // Batch listings
+    GroupValid,
+    GroupInvalid,
+    Line,
+    Undetected,
+    UndetectedCount,
+    UnknownLabel,
+    Mislabeled,
+    NotUtf8,
+}
+
+// The message text, with `{}` where the caller fills in values in order
+pub fn text(msg: Msg) -> &'static str {
+    match (lang(), msg) {
+        (Lang::En, Msg::Valid) => "Address is valid! ({})",
+        (Lang::En, Msg::Invalid) => "Invalid address! ({})",
+        (Lang::En, Msg::Details) => "Validation details:",
+        (Lang::En, Msg::ChainDetails) => "{} validation details:",
+        (Lang::En, Msg::ValidFor) => "Address is valid for:",
+        (Lang::En, Msg::ValidForNone) => "Address is not valid for any supported blockchain",
+        (Lang::En, Msg::Summary) => "Total: {}, valid: {}, invalid: {}",
+        (Lang::En, Msg::Example) => "A valid {} address looks like: {}",
+        (Lang::En, Msg::DetectedEns) => {
+            "Detected an ENS name: contains a dot, ends in a known TLD, no 0x prefix"
+        }
+        (Lang::En, Msg::DetectedRaw) => "Detected a raw address: not shaped like an ENS name",
+        (Lang::En, Msg::ChecksumDiff) => "Checksum diff ({} of {} characters differ in case):",
+        (Lang::En, Msg::DiffGiven) => "given:",
+        (Lang::En, Msg::DiffExpected) => "expected:",
+        (Lang::En, Msg::Actual) => "{} (actual: {})",
+        (Lang::En, Msg::ActualVersion) => "{} (actual: {}, version byte {})",
+        (Lang::En, Msg::OffBy) => "{}, off by {})",
+        (Lang::En, Msg::TooShort) => "too short ({} {}, expected {})",
+        (Lang::En, Msg::SkippedLowercase) => "skipped (all lowercase)",
+        (Lang::En, Msg::AllLowercase) => {
+            "Address is all lowercase, so it has no checksum protection against typos. The \
+             checksummed form is safer: {}"
+        }
+        (Lang::En, Msg::RemovedAnnotation) => "Removed the trailing annotation {}",
+        (Lang::En, Msg::NetworkMismatch) => "Valid {} address, but {} was requested",
+        (Lang::En, Msg::NearMissShort) => {
+            "Length is {} {} short of a valid length; the address was likely truncated"
+        }
+        (Lang::En, Msg::NearMissOver) => {
+            "Length is {} {} over a valid length; the address likely has stray characters"
+        }
+        (Lang::En, Msg::Taproot) => {
+            "Taproot addresses are commonly used to hold Ordinals inscriptions and Runes"
+        }
+        (Lang::En, Msg::ScriptHash) => {
+            "{} addresses pay to a hash of a {} script, which may be a multisig or any other \
+             spending policy; its contents can't be determined from the address alone"
+        }
+        (Lang::En, Msg::DustLimit) => {
+            "Dust limit: {} outputs below {} sats are not relayed by default"
+        }
+        (Lang::En, Msg::ForeignHrp) => {
+            "Checksum-valid bech32 with HRP '{}', which this chain doesn't use; it may be an \
+             address of another chain"
+        }
+        (Lang::En, Msg::NoSingleFix) => {
+            "No single-character substitution gives a valid checksum; the address has more than \
+             one error or was truncated"
+        }
+        (Lang::En, Msg::LikelyTypo) => {
+            "Likely typo at position {}: '{}' instead of '{}' gives a valid checksum ({}); \
+             confirm against the source before using it"
+        }
+        (Lang::En, Msg::SeveralFixes) => {
+            "A single-character fix at any of positions {} gives a valid checksum; compare those \
+             characters against the source"
+        }
+        (Lang::En, Msg::OnCurve) => {
+            "On-curve: likely a wallet (a heuristic; program ids are on-curve too)"
+        }
+        (Lang::En, Msg::OffCurve) => {
+            "Off-curve: a program-derived address (PDA), which no private key controls"
+        }
+        (Lang::En, Msg::ShortMove) => {
+            "Short address ({} of {} hex digits) is zero-padded to {}; short addresses usually \
+             denote framework or system accounts, so check it wasn't truncated"
+        }
+        (Lang::En, Msg::PolicyRejected) => {
+            "Well-formed address, but its {} is not accepted by the policy"
+        }
+        (Lang::En, Msg::PolicyAddressType) => "address type",
+        (Lang::En, Msg::PolicyNetwork) => "network",
+        (Lang::En, Msg::PrivateKey) => {
+            "This is a private extended key; anyone who sees it can spend the wallet's funds"
+        }
+        (Lang::En, Msg::TokenAware) => {
+            "Token-aware address: it signals CashTokens support, and wallets without it may not \
+             recognize it"
+        }
+        (Lang::En, Msg::NotTokenAware) => {
+            "Not token-aware: the receiving wallet doesn't signal CashTokens support, so don't \
+             send tokens to it"
+        }
+        (Lang::En, Msg::LegacyCash) => {
+            "Legacy address format, which Bitcoin addresses share; Bitcoin Cash wallets use the \
+             CashAddr form (bitcoincash:...)"
+        }
+        (Lang::En, Msg::EnsNonAscii) => {
+            "Name contains non-ASCII characters; check its ENSIP-15 normalized form"
+        }
+        (Lang::En, Msg::Note) => "Note: {}",
+        (Lang::En, Msg::RiskSameFamily) => {
+            "Confusion risk: {} all accept {} addresses, so the same string is valid on each; \
+             funds sent on the wrong chain are only recoverable with the same key"
+        }
+        (Lang::En, Msg::RiskUnrelated) => {
+            "Confusion risk: the input passes as {} at once; these formats are unrelated, so \
+             whoever holds the key on one chain need not control it on another"
+        }
+        (Lang::En, Msg::And) => " and ",
+        (Lang::En, Msg::NoOverlap) => {
+            "No cross-chain overlap: the input is valid on one chain only"
+        }
+        (Lang::En, Msg::GroupValid) => "Valid ({}):",
+        (Lang::En, Msg::GroupInvalid) => "Invalid ({}):",
+        (Lang::En, Msg::Line) => "line {}",
+        (Lang::En, Msg::Undetected) => "{} (undetected)",
+        (Lang::En, Msg::UndetectedCount) => "undetected: {}",
+        (Lang::En, Msg::UnknownLabel) => "{} (unknown blockchain label '{}')",
+        (Lang::En, Msg::Mislabeled) => "{} (labeled {}, but valid for: {})",
+        (Lang::En, Msg::NotUtf8) => "not valid UTF-8",
+        (Lang::De, Msg::Valid) => "Adresse ist gültig! ({})",
+        (Lang::De, Msg::Invalid) => "Ungültige Adresse! ({})",
+        (Lang::De, Msg::Details) => "Prüfdetails:",
+        (Lang::De, Msg::ChainDetails) => "Prüfdetails für {}:",
+        (Lang::De, Msg::ValidFor) => "Adresse ist gültig für:",
+        (Lang::De, Msg::ValidForNone) => "Adresse ist für keine unterstützte Blockchain gültig",
+        (Lang::De, Msg::Summary) => "Gesamt: {}, gültig: {}, ungültig: {}",
+        (Lang::De, Msg::Example) => "So sieht eine gültige {}-Adresse aus: {}",
+        (Lang::De, Msg::DetectedEns) => {
+            "ENS-Name erkannt: enthält einen Punkt, endet auf eine bekannte TLD, kein 0x-Präfix"
+        }
+        (Lang::De, Msg::DetectedRaw) => "Rohe Adresse erkannt: nicht wie ein ENS-Name aufgebaut",
+        (Lang::De, Msg::ChecksumDiff) => {
+            "Prüfsummenabweichung ({} von {} Zeichen weichen in der Schreibung ab):"
+        }
+        (Lang::De, Msg::DiffGiven) => "angegeben:",
+        (Lang::De, Msg::DiffExpected) => "erwartet:",
+        (Lang::De, Msg::Actual) => "{} (tatsächlich: {})",
+        (Lang::De, Msg::ActualVersion) => "{} (tatsächlich: {}, Versionsbyte {})",
+        (Lang::De, Msg::OffBy) => "{}, Abweichung {})",
+        (Lang::De, Msg::TooShort) => "zu kurz ({} {}, erwartet {})",
+        (Lang::De, Msg::SkippedLowercase) => "übersprungen (nur Kleinbuchstaben)",
+        (Lang::De, Msg::AllLowercase) => {
+            "Adresse besteht nur aus Kleinbuchstaben und ist daher nicht durch eine Prüfsumme \
+             gegen Tippfehler geschützt. Sicherer ist die Form mit Prüfsumme: {}"
+        }
+        (Lang::De, Msg::RemovedAnnotation) => "Angehängte Anmerkung {} entfernt",
+        (Lang::De, Msg::NetworkMismatch) => "Gültige {}-Adresse, angefordert war aber {}",
+        (Lang::De, Msg::NearMissShort) => {
+            "Die Länge liegt {} {} unter einer gültigen Länge; die Adresse wurde \
+             wahrscheinlich abgeschnitten"
+        }
+        (Lang::De, Msg::NearMissOver) => {
+            "Die Länge liegt {} {} über einer gültigen Länge; die Adresse enthält \
+             wahrscheinlich überzählige Zeichen"
+        }
+        (Lang::De, Msg::Taproot) => {
+            "Taproot-Adressen werden häufig für Ordinals-Inskriptionen und Runes verwendet"
+        }
+        (Lang::De, Msg::ScriptHash) => {
+            "{}-Adressen zahlen an den Hash eines {}-Skripts, das ein Multisig oder eine \
+             beliebige andere Ausgaberegel sein kann; sein Inhalt lässt sich aus der Adresse \
+             allein nicht bestimmen"
+        }
+        (Lang::De, Msg::DustLimit) => {
+            "Dust-Grenze: {}-Ausgaben unter {} Sats werden standardmäßig nicht weitergeleitet"
+        }
+        (Lang::De, Msg::ForeignHrp) => {
+            "Bech32 mit gültiger Prüfsumme und dem HRP '{}', den diese Chain nicht verwendet; \
+             es kann eine Adresse einer anderen Chain sein"
+        }
+        (Lang::De, Msg::NoSingleFix) => {
+            "Keine Ersetzung eines einzelnen Zeichens ergibt eine gültige Prüfsumme; die Adresse \
+             enthält mehr als einen Fehler oder wurde abgeschnitten"
+        }
+        (Lang::De, Msg::LikelyTypo) => {
+            "Wahrscheinlich ein Tippfehler an Position {}: '{}' statt '{}' ergibt eine gültige \
+             Prüfsumme ({}); vor der Verwendung mit der Quelle abgleichen"
+        }
+        (Lang::De, Msg::SeveralFixes) => {
+            "Eine Korrektur eines einzelnen Zeichens an einer der Positionen {} ergibt eine \
+             gültige Prüfsumme; diese Zeichen mit der Quelle vergleichen"
+        }
+        (Lang::De, Msg::OnCurve) => {
+            "Auf der Kurve: wahrscheinlich eine Wallet (eine Heuristik; auch Programm-IDs liegen \
+             auf der Kurve)"
+        }
+        (Lang::De, Msg::OffCurve) => {
+            "Nicht auf der Kurve: eine programmabgeleitete Adresse (PDA), die kein privater \
+             Schlüssel kontrolliert"
+        }
+        (Lang::De, Msg::ShortMove) => {
+            "Kurze Adresse ({} von {} Hex-Ziffern) wird mit Nullen auf {} aufgefüllt; kurze \
+             Adressen bezeichnen meist Framework- oder Systemkonten, also prüfen, dass sie nicht \
+             abgeschnitten wurde"
+        }
+        (Lang::De, Msg::PolicyRejected) => {
+            "Wohlgeformte Adresse, aber die Richtlinie lässt {} nicht zu"
+        }
+        (Lang::De, Msg::PolicyAddressType) => "Adresstyp",
+        (Lang::De, Msg::PolicyNetwork) => "Netzwerk",
+        (Lang::De, Msg::PrivateKey) => {
+            "Dies ist ein privater erweiterter Schlüssel; wer ihn sieht, kann über das Guthaben \
+             der Wallet verfügen"
+        }
+        (Lang::De, Msg::TokenAware) => {
+            "Token-fähige Adresse: sie signalisiert CashTokens-Unterstützung, und Wallets ohne \
+             diese erkennen sie womöglich nicht"
+        }
+        (Lang::De, Msg::NotTokenAware) => {
+            "Nicht token-fähig: die empfangende Wallet signalisiert keine \
+             CashTokens-Unterstützung, also keine Tokens an sie senden"
+        }
+        (Lang::De, Msg::LegacyCash) => {
+            "Altes Adressformat, das auch Bitcoin-Adressen verwenden; Bitcoin-Cash-Wallets \
+             verwenden die CashAddr-Form (bitcoincash:...)"
+        }
+        (Lang::De, Msg::EnsNonAscii) => {
+            "Der Name enthält Nicht-ASCII-Zeichen; seine nach ENSIP-15 normalisierte Form prüfen"
+        }
+        (Lang::De, Msg::Note) => "Hinweis: {}",
+        (Lang::De, Msg::RiskSameFamily) => {
+            "Verwechslungsgefahr: {} akzeptieren alle {}-Adressen, dieselbe Zeichenfolge ist \
+             also auf jeder gültig; auf der falschen Chain gesendetes Guthaben lässt sich nur mit \
+             demselben Schlüssel zurückholen"
+        }
+        (Lang::De, Msg::RiskUnrelated) => {
+            "Verwechslungsgefahr: die Eingabe gilt zugleich als {}; diese Formate hängen nicht \
+             zusammen, wer den Schlüssel auf einer Chain hat, kontrolliert ihn also nicht \
+             unbedingt auf einer anderen"
+        }
+        (Lang::De, Msg::And) => " und ",
+        (Lang::De, Msg::NoOverlap) => {
+            "Keine Überschneidung zwischen Chains: die Eingabe ist nur auf einer Chain gültig"
+        }
+        (Lang::De, Msg::GroupValid) => "Gültig ({}):",
+        (Lang::De, Msg::GroupInvalid) => "Ungültig ({}):",
+        (Lang::De, Msg::Line) => "Zeile {}",
+        (Lang::De, Msg::Undetected) => "{} (nicht erkannt)",
+        (Lang::De, Msg::UndetectedCount) => "nicht erkannt: {}",
+        (Lang::De, Msg::UnknownLabel) => "{} (unbekannte Blockchain-Bezeichnung '{}')",
+        (Lang::De, Msg::Mislabeled) => "{} (als {} bezeichnet, aber gültig für: {})",
+        (Lang::De, Msg::NotUtf8) => "kein gültiges UTF-8",
+    }
+}
+
+// Fill the `{}` placeholders of a message in order
+pub fn fill(msg: Msg, values: &[&dyn std::fmt::Display]) -> String {
+    let mut parts = text(msg).split("{}");
+    let mut out = parts.next().unwrap_or_default().to_string();
+    for (part, value) in parts.zip(values) {
+        out.push_str(&value.to_string());
+        out.push_str(part);
+    }
+    out
+}
+
+// German check and detail names, matched on the part before any parenthesized detail
+const CHECK_NAMES_DE: &[(&str, &str)] = &[
+    ("Chain ID", "Chain-ID"),
+    ("Network", "Netzwerk"),
+    ("Non-empty", "Nicht leer"),
+    ("Starts with 0x", "Beginnt mit 0x"),
+    ("Starts with D", "Beginnt mit D"),
+    ("Length", "Länge"),
+    ("Input length", "Eingabelänge"),
+    ("Valid hex characters", "Gültige Hex-Zeichen"),
+    ("No internal separators", "Keine inneren Trennzeichen"),
+    ("EIP-55 checksum", "EIP-55-Prüfsumme"),
+    ("EIP-1191 checksum", "EIP-1191-Prüfsumme"),
+    ("Base58 characters", "Base58-Zeichen"),
+    ("Base58 decoding", "Base58-Dekodierung"),
+    ("Base58Check checksum", "Base58Check-Prüfsumme"),
+    ("First character", "Erstes Zeichen"),
+    ("Address type", "Adresstyp"),
+    ("Bech32 checksum", "Bech32-Prüfsumme"),
+    ("Bech32 data", "Bech32-Daten"),
+    ("Checksum variant", "Prüfsummenvariante"),
+    ("Decoded length", "Dekodierte Länge"),
+    ("Witness program", "Witness-Programm"),
+    (
+        "Equals expected address",
+        "Entspricht der erwarteten Adresse",
+    ),
+    ("Canonical form", "Kanonische Form"),
+    ("Matches pattern", "Entspricht dem Muster"),
+    ("Mainnet network", "Mainnet-Netzwerk"),
+    ("Requested network", "Angefordertes Netzwerk"),
+    ("Required address type", "Erforderlicher Adresstyp"),
+    ("Public key", "Öffentlicher Schlüssel"),
+    ("Policy", "Richtlinie"),
+    ("Policy: address type", "Richtlinie: Adresstyp"),
+    ("Policy: network", "Richtlinie: Netzwerk"),
+    ("On ed25519 curve", "Auf der Ed25519-Kurve"),
+    ("Known TLD", "Bekannte TLD"),
+    ("No empty labels", "Keine leeren Labels"),
+    ("Valid name characters", "Gültige Namenszeichen"),
+    ("Lowercase", "Kleinschreibung"),
+    ("Second-level name", "Name zweiter Ebene"),
+    ("Key type", "Schlüsseltyp"),
+    ("Key data prefix", "Präfix der Schlüsseldaten"),
+    (
+        "Depth, fingerprint and child number",
+        "Tiefe, Fingerabdruck und Kindnummer",
+    ),
+    ("Consistent case", "Einheitliche Schreibweise"),
+    ("CashAddr prefix", "CashAddr-Präfix"),
+    ("CashAddr checksum", "CashAddr-Prüfsumme"),
+    ("CashAddr payload", "CashAddr-Nutzdaten"),
+    ("Hash length", "Hash-Länge"),
+    ("Descriptor checksum", "Deskriptor-Prüfsumme"),
+    ("Network prefix", "Netzwerkpräfix"),
+    ("Protocol", "Protokoll"),
+    ("Actor ID", "Actor-ID"),
+    ("Namespace actor ID", "Actor-ID des Namensraums"),
+    ("Base32 characters", "Base32-Zeichen"),
+    ("Payload length", "Nutzdatenlänge"),
+    ("Blake2b checksum", "Blake2b-Prüfsumme"),
+    ("Script template", "Skriptvorlage"),
+    ("Version byte", "Versionsbyte"),
+];
+
+// A length unit such as "chars", singular for a count of one
+pub fn unit(unit: &str, count: usize) -> &str {
+    match (lang(), unit, count) {
+        (Lang::En, _, 1) => unit.trim_end_matches('s'),
+        (Lang::En, _, _) => unit,
+        (Lang::De, "chars", _) => "Zeichen",
+        (Lang::De, "hex digits", 1) => "Hex-Ziffer",
+        (Lang::De, "hex digits", _) => "Hex-Ziffern",
+        (Lang::De, "bytes", 1) => "Byte",
+        (Lang::De, "bytes", _) => "Bytes",
+        (Lang::De, _, _) => unit,
+    }
+}
+
+// A check name in the selected language, falling back to English
+pub fn check_name(name: &str) -> String {
+    if lang() == Lang::En {
+        return name.to_string();
+    }
+    let (head, detail) = match name.find(" (") {
+        Some(i) => name.split_at(i),
+        None => (name, ""),
+    };
+    match CHECK_NAMES_DE.iter().find(|(en, _)| *en == head) {
+        Some((_, de)) => format!("{}{}", de, detail),
+        None => name.to_string(),
+    }
+}
@@ -0,0 +1,88 @@
+// IOTA and Shimmer (Stardust) bech32 addresses: a type byte followed by a
+// 32-byte hash, bech32-encoded under a network HRP
+use crate::i18n::{self, Msg};
+use crate::{bech32, CheckCode, Network, ValidationResult};
+
+// Address kind for each type byte the protocol defines
+const ADDRESS_KINDS: &[(u8, &str)] = &[(0, "Ed25519"), (8, "Alias"), (16, "NFT")];
+
+pub fn validate_iota_address(address: &str, hrps: &[&str], result: &mut ValidationResult) {
+    let decoded = match bech32::decode(address) {
+        Ok(decoded) => decoded,
+        Err(err) => {
+            result.add_check(
+                CheckCode::Checksum,
+                "Bech32 checksum",
+                false,
+                err.to_string(),
+            );
+            return;
+        }
+    };
+    let checksum_ok = decoded.variant == bech32::Variant::Bech32;
+    result.add_check(
+        CheckCode::Checksum,
+        "Bech32 checksum",
+        checksum_ok,
+        format!("{} ({})", checksum_ok, decoded.variant),
+    );
+
+    let hrp_ok = hrps.contains(&decoded.hrp.as_str());
+    if hrp_ok {
+        result.network = Some(if decoded.hrp == hrps[0] {
+            Network::Mainnet
+        } else {
+            Network::Testnet
+        });
+    }
+    result.add_check(
+        CheckCode::Hrp,
+        "HRP",
+        hrp_ok,
+        if hrp_ok {
+            format!("'{}'", decoded.hrp)
+        } else {
+            format!(
+                "unexpected HRP '{}' (expected {})",
+                decoded.hrp,
+                hrps.join(", ")
+            )
+        },
+    );
+
+    let payload = match bech32::convert_bits(&decoded.data, 5, 8, false) {
+        Some(payload) => payload,
+        None => {
+            result.add_check(
+                CheckCode::Decoding,
+                "Bech32 data",
+                false,
+                "invalid padding".to_string(),
+            );
+            return;
+        }
+    };
+
+    let kind = payload
+        .first()
+        .and_then(|type_byte| ADDRESS_KINDS.iter().find(|(known, _)| known == type_byte));
+    result.add_check(
+        CheckCode::AddressType,
+        "Address type",
+        kind.is_some(),
+        match (kind, payload.first()) {
+            (Some((type_byte, name)), _) => format!("{} (type byte {})", name, type_byte),
+            (None, Some(type_byte)) => format!("unknown type byte {}", type_byte),
+            (None, None) => "empty payload".to_string(),
+        },
+    );
+
+    let length_ok = payload.len() == 33;
+    result.add_check(
+        CheckCode::DecodedLength,
+        "Decoded length (33 bytes)",
+        length_ok,
+        i18n::fill(Msg::Actual, &[&length_ok, &payload.len()]),
+    );
+    result.payload = payload.get(1..).map(<[u8]>::to_vec);
+}
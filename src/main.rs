@@ -1,217 +1,3151 @@
-use clap::Parser;
+// First, so that its outln! macro is in scope in the modules below
+#[macro_use]
+mod output;
+
+mod base32;
+mod base58check;
+mod batch;
+mod bech32;
+mod cashaddr;
+mod decred;
+mod derive;
+mod descriptor;
+mod eip681;
+mod ens;
+mod filecoin;
+mod i18n;
+mod iota;
+mod move_vm;
+mod policy;
+mod qr;
+#[cfg(feature = "rpc")]
+mod rpc;
+mod script;
+mod selftest;
+mod xpub;
+
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use i18n::{Mark, Msg};
 use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
+use std::fmt;
+use std::io::Write;
 use std::process;
+use std::time::{Duration, Instant};
 
 // Blockchain address validator
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     // The blockchain address to validate
-    #[arg(short, long)]
-    address: String,
+    #[arg(short, long, required_unless_present = "file")]
+    address: Option<String>,
+
+    // Treat --address as a scanned QR payload: remove a payment URI scheme
+    // and its parameters and lowercase uppercase bech32 before validating
+    #[arg(long, action, requires = "address")]
+    from_qr: bool,
+
+    // Validate every address in a file, one per line ("-" reads stdin)
+    #[arg(short, long, conflicts_with = "address")]
+    file: Option<String>,
+
+    // With --file, print only the summary counts
+    #[arg(long, action, requires = "file")]
+    count_only: bool,
+
+    // With --file, list only the invalid addresses; the summary still
+    // counts every address
+    #[arg(long, action, requires = "file", conflicts_with = "only_valid")]
+    only_invalid: bool,
+
+    // With --file, list only the valid addresses
+    #[arg(long, action, requires = "file")]
+    only_valid: bool,
+
+    // With --file, list the valid addresses and then the invalid ones, each
+    // in input order, instead of interleaving them
+    #[arg(
+        long,
+        action,
+        requires = "file",
+        conflicts_with_all = ["count_only", "only_valid", "only_invalid"]
+    )]
+    group_by_validity: bool,
+
+    // With --file and --blockchain all, credit each address to the one chain
+    // that most likely issued it: the first that accepts it in the built-in
+    // order, so eth before the other EVM chains and btc before ltc and bch
+    #[arg(long, action, requires = "file")]
+    best_match: bool,
+
+    // How --file lines are laid out
+    #[arg(long, value_enum, default_value_t = InputFormat::Plain, requires = "file")]
+    input_format: InputFormat,
+
+    // With --file, also write the final counts and duration as JSON to this path
+    #[arg(long, requires = "file")]
+    summary_out: Option<String>,
+
+    // The ndjson or json-array field, or csv column (header name or 0-based index) that
+    // holds the address
+    #[arg(long, default_value = "address", requires = "file")]
+    column: String,
 
-    // The blockchain type (eth, btc, sol)
-    #[arg(short, long, default_value = "eth")]
+    // What separates addresses in --file input: newline, whitespace, tab or
+    // a single character such as ','
+    #[arg(long, default_value = "newline", value_parser = parse_delimiter, requires = "file")]
+    delimiter: Delimiter,
+
+    // The blockchain type (eth, btc, sol, apt, sui, an EVM chain such as polygon
+    // or ens), all to list every chain an address is valid on, or auto to
+    // detect the one chain most likely to have issued it. Without the flag it
+    // is read from BV_BLOCKCHAIN, for environments that set the chain globally
+    #[arg(short, long, default_value = "eth", global = true)]
     blockchain: String,
 
+    // Validate --address against every chain and call out the chains whose
+    // formats it could be confused between
+    #[arg(long, action, conflicts_with_all = ["file", "blockchain"])]
+    compare_chains: bool,
+
+    // Look up whether a valid address has any on-chain history
+    #[cfg(feature = "rpc")]
+    #[arg(long, action, requires = "rpc", conflicts_with = "file")]
+    check_activity: bool,
+
+    // JSON-RPC (Ethereum-format chains) or Esplora REST (Bitcoin-family chains)
+    // endpoint used by --check-activity
+    #[cfg(feature = "rpc")]
+    #[arg(long)]
+    rpc: Option<String>,
+
+    // Seconds to wait for each --rpc request before retrying
+    #[cfg(feature = "rpc")]
+    #[arg(long, default_value_t = 10, requires = "rpc")]
+    rpc_timeout: u64,
+
+    // Remove spaces and dashes inside Ethereum-format hex before validating
+    #[arg(long, action)]
+    strip_internal_separators: bool,
+
+    // What --address holds; extended keys are only supported for btc
+    #[arg(long = "type", value_enum, default_value_t = ObjectType::Address, conflicts_with = "file")]
+    object_type: ObjectType,
+
+    // Also require the address to equal this known-good one, ignoring
+    // differences the format treats as insignificant (such as hex case)
+    #[arg(long, conflicts_with = "file")]
+    equals: Option<String>,
+
+    // Also require a valid address to match this regular expression, such as
+    // ^0xdead for a vanity prefix or \.eth$ for a suffix
+    #[arg(long, value_parser = parse_pattern)]
+    matches: Option<Regex>,
+
+    // Reject input that is well-formed but unsafe to use as given, such as an
+    // Ethereum-format address without its checksum casing
+    #[arg(long, action)]
+    strict: bool,
+
+    // Accept only the one canonical form of each address, such as the
+    // checksummed form of Ethereum hex or lowercase bech32, so an address is
+    // stored exactly one way
+    #[arg(long, action)]
+    canonical_only: bool,
+
+    // Fail any address not recognized as belonging to a main network, so
+    // testnet addresses and those whose network is unknown are rejected
+    #[arg(long, action)]
+    mainnet_only: bool,
+
+    // Require the address to belong to this network; a valid address of the
+    // other network is reported as a mismatch
+    #[arg(long, value_enum)]
+    network: Option<Network>,
+
+    // For Bitcoin-family chains, accept any base58check address whose version
+    // byte is this one (hex, such as 0x1e), for forks without their own validator
+    #[arg(long, value_parser = parse_version_byte)]
+    version_byte: Option<u8>,
+
+    // For Bitcoin-family chains, reject valid addresses of any other type
+    #[arg(long, value_enum)]
+    require_type: Option<RequiredType>,
+
+    // For Bitcoin-family chains, check that the address pays to this public
+    // key (hex, compressed or uncompressed) and report through which script
+    #[arg(long, value_parser = parse_pubkey)]
+    pubkey: Option<k256::PublicKey>,
+
+    // Enforce the address types and networks each chain accepts, as
+    // declared in a JSON file; see src/policy.rs for the format
+    #[arg(long)]
+    policy: Option<String>,
+
+    // When a base58check checksum fails, search for a single mistyped character
+    #[arg(long, action)]
+    suggest: bool,
+
+    // For sol, require the address to be an ed25519 curve point, rejecting
+    // off-curve program-derived addresses (PDAs)
+    #[arg(long, action)]
+    on_curve: bool,
+
+    // For sol, note whether a valid address is on the ed25519 curve (likely a
+    // wallet) or off it (likely a program-derived address), without
+    // affecting validity
+    #[arg(long, action)]
+    classify: bool,
+
+    // A length at most this far from a valid one is reported as a likely
+    // truncation or extra character (0 turns the hint off)
+    #[arg(long, default_value_t = 2)]
+    threshold: usize,
+
+    // Reject longer input as too long to be an address before running any
+    // decoder (0 turns the limit off). The default leaves room for the
+    // lightning invoices and output descriptors that are recognized and
+    // explained rather than just rejected.
+    #[arg(long, default_value_t = 1024)]
+    max_input_length: usize,
+
+    // Keep checking after the first failure, so that every problem with the
+    // address is reported rather than only the first
+    #[arg(long, action)]
+    validate_all_checks: bool,
+
     // Optional: Enable verbose output
-    #[arg(short, long, action)]
+    #[arg(short, long, action, global = true)]
     verbose: bool,
+
+    // When an Ethereum-format checksum fails, show the given and expected
+    // casing with the differing characters marked (also shown with --verbose)
+    #[arg(long, action)]
+    diff: bool,
+
+    // With --verbose, show how long each check took
+    #[arg(long, action, requires = "verbose", global = true)]
+    profile: bool,
+
+    // Always exit 0; validity is still reported in the output
+    #[arg(long, action)]
+    report_only: bool,
+
+    // When to color the pass/fail marks of verbose details; auto colors a
+    // terminal unless NO_COLOR is set
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto, global = true)]
+    color: ColorChoice,
+
+    // Mark results with [PASS], [FAIL] and [WARN] instead of emoji
+    #[arg(long, visible_alias = "ascii", action, global = true)]
+    no_emoji: bool,
+
+    // Omit the trailing machine-readable RESULT line from text output
+    #[arg(short, long, action, global = true)]
+    quiet: bool,
+
+    // Output format for single-address results
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    format: OutputFormat,
+
+    // With --format raw, write the payload bytes instead of hex
+    #[arg(long, action)]
+    binary: bool,
+
+    // Add informational notes about address types to verbose and JSON output
+    #[arg(long, action)]
+    annotations: bool,
+
+    // Also print each matching chain's SLIP-44 coin type
+    #[arg(long, action)]
+    slip44: bool,
+
+    // Language of the text output (defaults to LANG, then English)
+    #[arg(long, value_enum)]
+    lang: Option<i18n::Lang>,
+
+    // Write the results to this file instead of stdout; progress and
+    // diagnostics stay on stderr
+    #[arg(short, long)]
+    output: Option<String>,
+
+    // A benchmark aid, hidden from --help: validate --address this many times
+    // and report the time taken on stderr before the usual output
+    #[arg(long, hide = true, default_value_t = 1, conflicts_with = "file")]
+    repeat: u32,
+
+    // Print the JSON Schema of the --format json output and exit
+    #[arg(long, action, exclusive = true)]
+    json_schema: bool,
+
+    // Validate the built-in set of known-good and known-bad inputs for every
+    // chain, reporting any with an unexpected result, and exit
+    #[arg(long, action, exclusive = true)]
+    selftest: bool,
 }
 
-#[derive(Debug)]
+#[derive(Subcommand, Debug)]
+enum Command {
+    // Derive the address at a BIP-32 path of an extended key and validate it
+    Derive {
+        // Extended public or private key (xpub, zpub, xprv, ...); hardened
+        // path steps need a private key
+        #[arg(long, required_unless_present = "create2")]
+        xpub: Option<String>,
+
+        // Derivation path such as m/44'/60'/0'/0/0
+        #[arg(long, required_unless_present = "create2")]
+        path: Option<String>,
+
+        // Compute the address a CREATE2 deployment will have (EIP-1014)
+        // instead of deriving from a key
+        #[arg(
+            long,
+            action,
+            conflicts_with_all = ["xpub", "path"],
+            requires_all = ["deployer", "salt", "init_code_hash"]
+        )]
+        create2: bool,
+
+        // With --create2, the deploying contract's address
+        #[arg(long, requires = "create2")]
+        deployer: Option<String>,
+
+        // With --create2, the 32-byte salt as hex
+        #[arg(long, requires = "create2")]
+        salt: Option<String>,
+
+        // With --create2, the Keccak-256 hash of the contract's init code
+        #[arg(long, requires = "create2")]
+        init_code_hash: Option<String>,
+    },
+    // Print the checksummed form of each Ethereum-format address in a file,
+    // one per line ("-" reads stdin), flagging the invalid ones
+    Checksum {
+        #[arg(short, long)]
+        file: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+// How verbose check details are printed
+#[derive(Debug, Clone, Copy)]
+struct DetailStyle {
+    profile: bool,
+    color: bool,
+}
+
+impl DetailStyle {
+    fn from_args(args: &Args) -> Self {
+        let color = match args.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && output::is_terminal()
+            }
+        };
+        Self {
+            profile: args.profile,
+            color,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    // The decoded payload of a valid address (hash, key or witness program)
+    Raw,
+    // Shell variable assignments (BV_VALID=true ...) for eval
+    Env,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ObjectType {
+    Address,
+    // A BIP-32 extended public or private key (xpub, ypub, zpub, tpub, ...)
+    Xpub,
+    // A Bitcoin output script (scriptPubKey) as hex, such as 76a914...88ac
+    Script,
+}
+
+// Settings that change how addresses are validated
+#[derive(Debug, Default, Clone)]
+struct ValidationOptions {
+    strip_internal_separators: bool,
+    equals: Option<String>,
+    matches: Option<Regex>,
+    mainnet_only: bool,
+    strict: bool,
+    canonical_only: bool,
+    network: Option<Network>,
+    on_curve: bool,
+    classify: bool,
+    threshold: usize,
+    max_input_length: usize,
+    all_checks: bool,
+    annotations: bool,
+    suggest: bool,
+    version_byte: Option<u8>,
+    require_type: Option<RequiredType>,
+    pubkey: Option<k256::PublicKey>,
+    policy: Option<policy::Policy>,
+}
+
+impl ValidationOptions {
+    fn from_args(args: &Args) -> Self {
+        Self {
+            strip_internal_separators: args.strip_internal_separators,
+            equals: args.equals.clone(),
+            matches: args.matches.clone(),
+            mainnet_only: args.mainnet_only,
+            strict: args.strict,
+            canonical_only: args.canonical_only,
+            network: args.network,
+            on_curve: args.on_curve,
+            classify: args.classify,
+            threshold: args.threshold,
+            max_input_length: args.max_input_length,
+            all_checks: args.validate_all_checks,
+            annotations: args.annotations,
+            suggest: args.suggest,
+            version_byte: args.version_byte,
+            require_type: args.require_type,
+            pubkey: args.pubkey,
+            policy: args.policy.as_deref().map(|path| {
+                policy::load(path).unwrap_or_else(|err| {
+                    eprintln!("Failed to load policy {}: {}", path, err);
+                    process::exit(1);
+                })
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    // One address per line
+    Plain,
+    // An optional chain label before each address: `eth,0x...` or `btc 1...`
+    Labeled,
+    // One JSON object per line with "address" and optional "blockchain";
+    // results are written as NDJSON
+    #[value(alias = "json")]
+    Ndjson,
+    // A single JSON array of such objects (or bare address strings), read
+    // whole; results are written as one JSON array
+    JsonArray,
+    // CSV with a header row; each row is written back with "valid",
+    // "matches" and "line" columns added
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delimiter {
+    Newline,
+    Whitespace,
+    Char(char),
+}
+
+impl Delimiter {
+    // The tokens of one input line
+    fn split<'a>(&self, line: &'a str) -> Vec<&'a str> {
+        match self {
+            Delimiter::Newline => vec![line],
+            Delimiter::Whitespace => line.split_whitespace().collect(),
+            Delimiter::Char(c) => line.split(*c).collect(),
+        }
+    }
+}
+
+fn parse_delimiter(value: &str) -> Result<Delimiter, String> {
+    match value {
+        "newline" => Ok(Delimiter::Newline),
+        "whitespace" => Ok(Delimiter::Whitespace),
+        "tab" => Ok(Delimiter::Char('\t')),
+        _ => {
+            let mut chars = value.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => Ok(Delimiter::Char(c)),
+                _ => Err("expected newline, whitespace, tab or a single character".to_string()),
+            }
+        }
+    }
+}
+
+fn parse_version_byte(value: &str) -> Result<u8, String> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    u8::from_str_radix(digits, 16).map_err(|_| "expected one byte of hex, such as 0x1e".to_string())
+}
+
+fn parse_pubkey(value: &str) -> Result<k256::PublicKey, String> {
+    let bytes = hex::decode(value.strip_prefix("0x").unwrap_or(value))
+        .map_err(|err| format!("not hex: {}", err))?;
+    k256::PublicKey::from_sec1_bytes(&bytes)
+        .map_err(|_| "not a secp256k1 public key (33 or 65 bytes)".to_string())
+}
+
+fn parse_pattern(value: &str) -> Result<Regex, String> {
+    Regex::new(value).map_err(|err| err.to_string())
+}
+
+// Stable identifiers for the kinds of check a validator runs. Check names
+// are for people and may change; these codes are part of the JSON output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum CheckCode {
+    NonEmpty,
+    Prefix,
+    Length,
+    HexCharacters,
+    Separators,
+    Base58Characters,
+    FirstCharacter,
+    AddressType,
+    Hrp,
+    Decoding,
+    DecodedLength,
+    Checksum,
+    ChecksumVariant,
+    Tld,
+    NameCharacters,
+    Equals,
+    Canonical,
+    Pattern,
+    Policy,
+    PublicKey,
+    Network,
+    OnCurve,
+    Base32Characters,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+struct Check {
+    code: CheckCode,
+    name: String,
+    passed: bool,
+    message: String,
+    // Time spent since the previous check (or since validation started)
+    #[serde(skip)]
+    elapsed: Duration,
+}
+
+// The lengths a format accepts for its address strings, declared once per
+// format and checked by `ValidationResult::add_length_check`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lengths {
+    Exactly(usize),
+    Either(usize, usize),
+    Range(usize, usize),
+    AtMost(usize),
+}
+
+impl Lengths {
+    fn contains(self, length: usize) -> bool {
+        match self {
+            Lengths::Exactly(expected) => length == expected,
+            Lengths::Either(a, b) => length == a || length == b,
+            Lengths::Range(min, max) => (min..=max).contains(&length),
+            Lengths::AtMost(max) => length <= max,
+        }
+    }
+
+    // How far a length is from the nearest accepted one: negative when it
+    // is short, positive when it is long
+    fn offset(self, length: usize) -> isize {
+        let offset = |expected: usize| length as isize - expected as isize;
+        match self {
+            _ if self.contains(length) => 0,
+            Lengths::Exactly(expected) => offset(expected),
+            Lengths::Either(a, b) => match (offset(a), offset(b)) {
+                (a, b) if a.abs() <= b.abs() => a,
+                (_, b) => b,
+            },
+            Lengths::Range(min, _) if length < min => offset(min),
+            Lengths::Range(_, max) | Lengths::AtMost(max) => offset(max),
+        }
+    }
+}
+
+impl fmt::Display for Lengths {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Lengths::Exactly(expected) => write!(f, "{}", expected),
+            Lengths::Either(a, b) => write!(f, "{} or {}", a, b),
+            Lengths::Range(min, max) => write!(f, "{}-{}", min, max),
+            Lengths::AtMost(max) => write!(f, "at most {}", max),
+        }
+    }
+}
+
+// The network an address belongs to, where its format or chain says
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+enum Network {
+    Mainnet,
+    Testnet,
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+        })
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 struct ValidationResult {
     valid: bool,
-    details: Vec<(String, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    network: Option<Network>,
+    details: Vec<Check>,
+    // Advisories that don't affect validity
+    warnings: Vec<String>,
+    // Background on the address type from --annotations
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
+    // The decoded address body (hash, key or program) for --format raw
+    #[serde(skip)]
+    payload: Option<Vec<u8>>,
+    // A failed length check's distance from the nearest valid length, and
+    // its unit, for the --threshold hint
+    #[serde(skip)]
+    length_miss: Option<(isize, &'static str)>,
+    #[serde(skip)]
+    last_check: Instant,
+}
+
+// A validation result as emitted by --format json
+#[derive(Serialize, JsonSchema)]
+struct Report<'a> {
+    blockchain: &'a str,
+    // Included with --slip44 for chains that have a registered coin type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slip44: Option<u32>,
+    address: &'a str,
+    #[serde(flatten)]
+    result: &'a ValidationResult,
 }
 
 impl ValidationResult {
     fn new() -> Self {
         Self {
             valid: true,
+            network: None,
             details: Vec::new(),
+            warnings: Vec::new(),
+            notes: Vec::new(),
+            payload: None,
+            length_miss: None,
+            last_check: Instant::now(),
         }
     }
 
-    fn add_check(&mut self, check: &str, result: bool, message: String) {
+    // Back to the state of `new`, keeping the lists' capacity
+    fn clear(&mut self) {
+        self.valid = true;
+        self.network = None;
+        self.details.clear();
+        self.warnings.clear();
+        self.notes.clear();
+        self.payload = None;
+        self.length_miss = None;
+        self.last_check = Instant::now();
+    }
+
+    fn add_warning(&mut self, message: String) {
+        self.warnings.push(message);
+    }
+
+    // Check a length in `unit`s (such as "chars") against what the format accepts
+    fn add_length_check(&mut self, expected: Lengths, unit: &'static str, actual: usize) {
+        let length_ok = expected.contains(actual);
+        if !length_ok {
+            self.length_miss = Some((expected.offset(actual), unit));
+        }
+        self.add_check(
+            CheckCode::Length,
+            &format!("Length ({} {})", expected, unit),
+            length_ok,
+            i18n::fill(Msg::Actual, &[&length_ok, &actual]),
+        );
+    }
+
+    fn add_check(&mut self, code: CheckCode, check: &str, result: bool, message: String) {
         self.valid = self.valid && result;
-        self.details.push((check.to_string(), message));
+        let now = Instant::now();
+        self.details.push(Check {
+            code,
+            name: check.to_string(),
+            passed: result,
+            message,
+            elapsed: now - self.last_check,
+        });
+        self.last_check = now;
+    }
+}
+
+// How a chain's addresses are validated
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    // Ethereum-style 0x hex address of `address_bytes` bytes. EIP-1191 chains mix
+    // their chain id into the checksum.
+    Eth {
+        chain_id: Option<u64>,
+        address_bytes: usize,
+        eip1191: bool,
+    },
+    Btc(&'static BtcParams),
+    Sol,
+    Dcr,
+    Fil,
+    // Move-based chains: up to 32 bytes of 0x hex, short forms zero-padded
+    Move,
+    // A human-readable ENS name that resolves to an Ethereum address
+    Ens,
+    // IOTA Stardust bech32 address, with the HRPs of the chain's networks,
+    // mainnet first
+    Iota(&'static [&'static str]),
+    // Bitcoin Cash CashAddr, or a legacy base58 address
+    Cash,
+}
+
+impl Format {
+    // The address family a format belongs to; chains in one family share an
+    // encoding, so the same string can be valid on each of them
+    fn family(&self) -> &'static str {
+        match self {
+            Format::Eth { .. } => "Ethereum-format hex",
+            Format::Btc(_) => "Bitcoin-family base58/bech32",
+            Format::Sol => "Solana base58",
+            Format::Dcr => "Decred base58",
+            Format::Fil => "Filecoin base32",
+            Format::Move => "Move hex",
+            Format::Ens => "ENS name",
+            Format::Iota(_) => "IOTA bech32",
+            Format::Cash => "Bitcoin Cash CashAddr",
+        }
+    }
+}
+
+// A supported blockchain and the validator for its address format
+struct Chain {
+    name: &'static str,
+    label: &'static str,
+    format: Format,
+}
+
+// Every supported blockchain, in the order `--blockchain all` reports them.
+// EVM-compatible chains are aliases that reuse the Ethereum validator.
+const CHAINS: &[Chain] = &[
+    Chain {
+        name: "eth",
+        label: "Ethereum",
+        format: Format::Eth {
+            chain_id: Some(1),
+            address_bytes: 20,
+            eip1191: false,
+        },
+    },
+    Chain {
+        name: "bsc",
+        label: "BNB Chain",
+        format: Format::Eth {
+            chain_id: Some(56),
+            address_bytes: 20,
+            eip1191: false,
+        },
+    },
+    Chain {
+        name: "polygon",
+        label: "Polygon",
+        format: Format::Eth {
+            chain_id: Some(137),
+            address_bytes: 20,
+            eip1191: false,
+        },
+    },
+    Chain {
+        name: "avalanche",
+        label: "Avalanche C-Chain",
+        format: Format::Eth {
+            chain_id: Some(43114),
+            address_bytes: 20,
+            eip1191: false,
+        },
+    },
+    Chain {
+        name: "arbitrum",
+        label: "Arbitrum One",
+        format: Format::Eth {
+            chain_id: Some(42161),
+            address_bytes: 20,
+            eip1191: false,
+        },
+    },
+    Chain {
+        name: "optimism",
+        label: "OP Mainnet",
+        format: Format::Eth {
+            chain_id: Some(10),
+            address_bytes: 20,
+            eip1191: false,
+        },
+    },
+    Chain {
+        name: "fantom",
+        label: "Fantom",
+        format: Format::Eth {
+            chain_id: Some(250),
+            address_bytes: 20,
+            eip1191: false,
+        },
+    },
+    Chain {
+        name: "rsk",
+        label: "Rootstock",
+        format: Format::Eth {
+            chain_id: Some(30),
+            address_bytes: 20,
+            eip1191: true,
+        },
+    },
+    Chain {
+        name: "vet",
+        label: "VeChain",
+        format: Format::Eth {
+            chain_id: None,
+            address_bytes: 20,
+            eip1191: false,
+        },
+    },
+    Chain {
+        name: "ens",
+        label: "ENS name",
+        format: Format::Ens,
+    },
+    Chain {
+        name: "btc",
+        label: "Bitcoin",
+        format: Format::Btc(&BTC_PARAMS),
+    },
+    Chain {
+        name: "ltc",
+        label: "Litecoin",
+        format: Format::Btc(&LTC_PARAMS),
+    },
+    Chain {
+        name: "grs",
+        label: "Groestlcoin",
+        format: Format::Btc(&GRS_PARAMS),
+    },
+    Chain {
+        name: "bch",
+        label: "Bitcoin Cash",
+        format: Format::Cash,
+    },
+    Chain {
+        name: "dcr",
+        label: "Decred",
+        format: Format::Dcr,
+    },
+    Chain {
+        name: "fil",
+        label: "Filecoin",
+        format: Format::Fil,
+    },
+    Chain {
+        name: "sol",
+        label: "Solana",
+        format: Format::Sol,
+    },
+    Chain {
+        name: "apt",
+        label: "Aptos",
+        format: Format::Move,
+    },
+    Chain {
+        name: "sui",
+        label: "Sui",
+        format: Format::Move,
+    },
+    Chain {
+        name: "iota",
+        label: "IOTA",
+        format: Format::Iota(&["iota", "atoi"]),
+    },
+    Chain {
+        name: "smr",
+        label: "Shimmer",
+        format: Format::Iota(&["smr", "rms"]),
+    },
+];
+
+// SLIP-44 coin types wallets use in BIP-44 derivation paths. ENS names
+// aren't a coin and have none.
+const SLIP44_COIN_TYPES: &[(&str, u32)] = &[
+    ("btc", 0),
+    ("ltc", 2),
+    ("grs", 17),
+    ("bch", 145),
+    ("dcr", 42),
+    ("fil", 461),
+    ("eth", 60),
+    ("rsk", 137),
+    ("sol", 501),
+    ("optimism", 614),
+    ("apt", 637),
+    ("bsc", 714),
+    ("sui", 784),
+    ("vet", 818),
+    ("polygon", 966),
+    ("fantom", 1007),
+    ("iota", 4218),
+    ("smr", 4219),
+    ("avalanche", 9000),
+    ("arbitrum", 9001),
+];
+
+fn slip44_coin_type(chain: &Chain) -> Option<u32> {
+    SLIP44_COIN_TYPES
+        .iter()
+        .find(|(name, _)| *name == chain.name)
+        .map(|(_, coin_type)| *coin_type)
+}
+
+fn print_slip44(chain: &Chain) {
+    match slip44_coin_type(chain) {
+        Some(coin_type) => outln!("SLIP-44 coin type ({}): {}", chain.name, coin_type),
+        None => outln!("SLIP-44 coin type ({}): none registered", chain.name),
+    }
+}
+
+fn main() {
+    let args = parse_input();
+    let lang = args.lang.or_else(|| {
+        std::env::var("LANG")
+            .ok()
+            .and_then(|locale| i18n::Lang::from_locale(&locale))
+    });
+    // Structured output is for scripts, which read the same text in every language
+    let structured = args.format == OutputFormat::Json
+        || matches!(
+            args.input_format,
+            InputFormat::Ndjson | InputFormat::JsonArray | InputFormat::Csv
+        );
+    i18n::set_lang(lang.filter(|_| !structured).unwrap_or(i18n::Lang::En));
+    i18n::set_ascii(args.no_emoji);
+    if let Some(path) = &args.output {
+        match std::fs::File::create(path) {
+            Ok(file) => output::set_file(file),
+            Err(err) => {
+                eprintln!("Failed to create {}: {}", path, err);
+                process::exit(1);
+            }
+        }
+    }
+
+    if args.json_schema {
+        print_json(&schemars::schema_for!(Report));
+        return;
+    }
+    if args.selftest {
+        if !selftest::run() {
+            process::exit(1);
+        }
+        return;
+    }
+
+    if args.binary && args.format != OutputFormat::Raw {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--binary requires --format raw",
+            )
+            .exit();
+    }
+
+    let chains = selected_chains(&args.blockchain);
+    let options = ValidationOptions::from_args(&args);
+
+    let base58check_chains = chains
+        .iter()
+        .all(|chain| matches!(chain.format, Format::Btc(_)));
+    if args.version_byte.is_some() && !base58check_chains {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--version-byte is only supported for base58check chains (btc, ltc, grs)",
+            )
+            .exit();
+    }
+    if args.require_type.is_some() && !base58check_chains {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--require-type is only supported for Bitcoin-family chains (btc, ltc, grs)",
+            )
+            .exit();
+    }
+
+    let all_valid = if let Some(Command::Derive { .. }) = &args.command {
+        run_derive(&args, chains[0], &options)
+    } else if let Some(Command::Checksum { file }) = &args.command {
+        let ethereum_format = chains.len() == 1 && matches!(chains[0].format, Format::Eth { .. });
+        if !ethereum_format {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "checksum needs an Ethereum-format --blockchain",
+                )
+                .exit();
+        }
+        if matches!(args.format, OutputFormat::Raw | OutputFormat::Env) {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "checksum only supports --format text and json",
+                )
+                .exit();
+        }
+        batch::run_checksum(&args, file, chains[0], &options)
+    } else if let Some(path) = &args.file {
+        let json_input = matches!(
+            args.input_format,
+            InputFormat::Ndjson | InputFormat::JsonArray
+        );
+        if args.format != OutputFormat::Text && !json_input {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--format json, raw and env are only supported for a single --address",
+                )
+                .exit();
+        }
+        let text_input = matches!(args.input_format, InputFormat::Plain | InputFormat::Labeled);
+        if args.group_by_validity && !text_input {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--group-by-validity is only supported for plain and labeled input",
+                )
+                .exit();
+        }
+        if args.best_match && (chains.len() == 1 || json_input) {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--best-match needs --blockchain all and plain, labeled or csv input",
+                )
+                .exit();
+        }
+        batch::run(&args, path, &chains, &options)
+    } else {
+        let scanned = args
+            .from_qr
+            .then(|| qr::extract(args.address.as_deref().unwrap_or_default()));
+        let address = match &scanned {
+            Some(scanned) => scanned.address.as_str(),
+            None => args.address.as_deref().unwrap_or_default(),
+        };
+        if let Some(scanned) = scanned.as_ref().filter(|_| args.verbose) {
+            if args.format == OutputFormat::Text {
+                print_transformations(scanned);
+            }
+        }
+        if args.object_type != ObjectType::Address && args.blockchain != "btc" {
+            Args::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "--type xpub and script are only supported with --blockchain btc",
+                )
+                .exit();
+        }
+        if args.repeat > 1 {
+            print_benchmark(&chains, address, &options, args.repeat);
+        }
+        if args.compare_chains {
+            if args.format != OutputFormat::Text {
+                Args::command()
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        "--compare-chains only supports text output",
+                    )
+                    .exit();
+            }
+            let matches = validate_all(address, &options);
+            display_comparison(&matches);
+            if !args.quiet {
+                print_matches_line("all", matches_of(&matches));
+            }
+            matches.iter().any(|(_, result)| result.valid)
+        } else if eip681::is_uri(address) && args.object_type == ObjectType::Address {
+            if matches!(args.format, OutputFormat::Raw | OutputFormat::Env) {
+                Args::command()
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        "--format raw and env are not supported for payment request URIs",
+                    )
+                    .exit();
+            }
+            run_payment_request(&args, address, &options)
+        } else if args.blockchain == "all" {
+            if args.format == OutputFormat::Env {
+                Args::command()
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        "--format env needs a single --blockchain",
+                    )
+                    .exit();
+            }
+            let matches = validate_all(address, &options);
+            match args.format {
+                OutputFormat::Text => {
+                    display_matches(
+                        address,
+                        &matches,
+                        args.verbose,
+                        DetailStyle::from_args(&args),
+                    );
+                    if args.slip44 {
+                        for (chain, _) in matches.iter().filter(|(_, result)| result.valid) {
+                            print_slip44(chain);
+                        }
+                    }
+                    if !args.quiet {
+                        print_matches_line("all", matches_of(&matches));
+                    }
+                }
+                OutputFormat::Json => {
+                    let reports: Vec<Report> = matches
+                        .iter()
+                        .map(|(chain, result)| Report {
+                            blockchain: chain.name,
+                            slip44: slip44_coin_type(chain).filter(|_| args.slip44),
+                            address,
+                            result,
+                        })
+                        .collect();
+                    print_json(&reports);
+                }
+                OutputFormat::Raw => {
+                    if let Some((_, result)) = matches.iter().find(|(_, result)| result.valid) {
+                        print_raw(result, args.binary);
+                    }
+                }
+                OutputFormat::Env => unreachable!("rejected above"),
+            }
+            matches.iter().any(|(_, result)| result.valid)
+        } else if args.blockchain == "auto" {
+            if args.format == OutputFormat::Env {
+                Args::command()
+                    .error(
+                        ErrorKind::ArgumentConflict,
+                        "--format env needs a single --blockchain",
+                    )
+                    .exit();
+            }
+            let results = validate_all(address, &options);
+            // The first chain that accepts the address, as --best-match ranks them
+            let detected = results.iter().find(|(_, result)| result.valid);
+            match args.format {
+                OutputFormat::Text => {
+                    let style = DetailStyle::from_args(&args);
+                    match detected {
+                        Some((chain, result)) => {
+                            display_results(chain, result, args.verbose, style);
+                            if args.slip44 {
+                                print_slip44(chain);
+                            }
+                        }
+                        None => display_matches(address, &results, args.verbose, style),
+                    }
+                    if !args.quiet {
+                        print_matches_line("auto", detected.map(|(chain, _)| chain.name));
+                    }
+                }
+                OutputFormat::Json => {
+                    let reports: Vec<Report> = detected
+                        .iter()
+                        .map(|(chain, result)| Report {
+                            blockchain: chain.name,
+                            slip44: slip44_coin_type(chain).filter(|_| args.slip44),
+                            address,
+                            result,
+                        })
+                        .collect();
+                    print_json(&reports);
+                }
+                OutputFormat::Raw => {
+                    if let Some((_, result)) = detected {
+                        print_raw(result, args.binary);
+                    }
+                }
+                OutputFormat::Env => unreachable!("rejected above"),
+            }
+            detected.is_some()
+        } else {
+            let validation_result = match args.object_type {
+                ObjectType::Address => validate_address(chains[0], address, &options),
+                ObjectType::Xpub => xpub::validate_extended_key(address),
+                ObjectType::Script => script::validate_script(address),
+            };
+            match args.format {
+                OutputFormat::Text => {
+                    if args.object_type == ObjectType::Script {
+                        if let Some(script_address) = script::script_address(address) {
+                            outln!("Script address: {}", script_address);
+                        }
+                    }
+                    display_results(
+                        chains[0],
+                        &validation_result,
+                        args.verbose,
+                        DetailStyle::from_args(&args),
+                    );
+                    if args.diff || args.verbose {
+                        print_checksum_diff(
+                            chains[0],
+                            address,
+                            &validation_result,
+                            DetailStyle::from_args(&args),
+                        );
+                    }
+                    if args.slip44 {
+                        print_slip44(chains[0]);
+                    }
+                    if !args.quiet {
+                        print_result_line(chains[0], &validation_result);
+                    }
+                }
+                OutputFormat::Json => print_json(&Report {
+                    blockchain: chains[0].name,
+                    slip44: slip44_coin_type(chains[0]).filter(|_| args.slip44),
+                    address,
+                    result: &validation_result,
+                }),
+                OutputFormat::Raw => print_raw(&validation_result, args.binary),
+                OutputFormat::Env => print_env(chains[0], address, &validation_result),
+            }
+
+            #[cfg(feature = "rpc")]
+            if args.check_activity && validation_result.valid && args.format == OutputFormat::Text {
+                if let Some(rpc_url) = &args.rpc {
+                    let timeout = Duration::from_secs(args.rpc_timeout);
+                    rpc::report_activity(chains[0], address, rpc_url, timeout);
+                }
+            }
+
+            validation_result.valid
+        }
+    };
+
+    if !all_valid && !args.report_only {
+        process::exit(1);
+    }
+}
+
+fn run_derive(args: &Args, chain: &Chain, options: &ValidationOptions) -> bool {
+    if args.blockchain == "all" || args.blockchain == "auto" {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "derive needs a single --blockchain",
+            )
+            .exit();
+    }
+
+    let Some(Command::Derive {
+        xpub,
+        path,
+        deployer,
+        salt,
+        init_code_hash,
+        ..
+    }) = &args.command
+    else {
+        unreachable!("run_derive is only called for the derive command");
+    };
+    let (derived, source) = match (xpub, path, deployer, salt, init_code_hash) {
+        (Some(key), Some(path), ..) => (derive::derive_address(key, path, chain), path.as_str()),
+        (.., Some(deployer), Some(salt), Some(init_code_hash)) => (
+            derive::create2_address(deployer, salt, init_code_hash, chain),
+            "CREATE2",
+        ),
+        _ => unreachable!("clap requires a key and path, or the CREATE2 inputs"),
+    };
+    let address = match derived {
+        Ok(address) => address,
+        Err(err) => {
+            eprintln!("Derivation failed: {}", err);
+            process::exit(1);
+        }
+    };
+    outln!("Derived address ({}): {}", source, address);
+
+    let result = validate_address(chain, &address, options);
+    display_results(chain, &result, args.verbose, DetailStyle::from_args(args));
+    if !args.quiet {
+        print_result_line(chain, &result);
+    }
+    result.valid
+}
+
+fn print_transformations(scanned: &qr::Scanned) {
+    if scanned.transformations.is_empty() {
+        outln!("QR payload used as is\n");
+        return;
+    }
+    outln!("QR payload transformations:");
+    for transformation in &scanned.transformations {
+        outln!("- {}", transformation);
+    }
+    outln!("Address: {}\n", scanned.address);
+}
+
+// Validate the addresses in an EIP-681 URI: its target and any `address`
+// parameter, on the chain its chain id names. Without a chain id the
+// --blockchain chain is used if it is Ethereum-format, else Ethereum.
+fn run_payment_request(args: &Args, uri: &str, options: &ValidationOptions) -> bool {
+    let request = match eip681::parse(uri) {
+        Ok(request) => request,
+        Err(err) => {
+            eprintln!("Invalid payment request URI: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let is_eth = |chain: &&'static Chain| matches!(chain.format, Format::Eth { .. });
+    let unknown_chain;
+    let chain = match request.chain_id {
+        Some(chain_id) => match registered_eth_chain(chain_id) {
+            Some(chain) => chain,
+            None => {
+                unknown_chain = unregistered_eth_chain(chain_id);
+                &unknown_chain
+            }
+        },
+        None => find_chain(&args.blockchain)
+            .filter(is_eth)
+            .or_else(|| find_chain("eth"))
+            .expect("eth is a supported chain"),
+    };
+    let target_chain = if ens::looks_like_ens_name(request.target) {
+        find_chain("ens").expect("ens is a supported chain")
+    } else {
+        chain
+    };
+
+    let mut addresses = vec![("Target", request.target, target_chain)];
+    if let Some(parameter) = request.address_parameter {
+        addresses.push(("Address parameter", parameter, chain));
+    }
+    let results: Vec<(&str, &str, &Chain, ValidationResult)> = addresses
+        .into_iter()
+        .map(|(role, address, chain)| {
+            (
+                role,
+                address,
+                chain,
+                validate_address(chain, address, options),
+            )
+        })
+        .collect();
+
+    if args.format == OutputFormat::Json {
+        let reports: Vec<Report> = results
+            .iter()
+            .map(|(_, address, chain, result)| Report {
+                blockchain: chain.name,
+                slip44: slip44_coin_type(chain).filter(|_| args.slip44),
+                address,
+                result,
+            })
+            .collect();
+        print_json(&reports);
+    } else {
+        let chain_id = match request.chain_id {
+            Some(chain_id) => format!("chain id {}", chain_id),
+            None => "no chain id".to_string(),
+        };
+        match request.function {
+            Some(function) => outln!(
+                "Payment request for {} ({}), function {}",
+                chain.label,
+                chain_id,
+                function
+            ),
+            None => outln!("Payment request for {} ({})", chain.label, chain_id),
+        }
+        if chain.name == "evm" {
+            outln!(
+                "{} Chain id {} isn't a supported chain; addresses are checked with EIP-55",
+                i18n::mark(Mark::Warning),
+                request.chain_id.unwrap_or_default()
+            );
+        }
+        for (role, address, chain, result) in &results {
+            outln!("\n{}: {}", role, address);
+            display_results(chain, result, args.verbose, DetailStyle::from_args(args));
+        }
+        if !args.quiet {
+            outln!();
+            for (_, _, chain, result) in &results {
+                print_result_line(chain, result);
+            }
+        }
+    }
+
+    results.iter().all(|(_, _, _, result)| result.valid)
+}
+
+fn parse_input() -> Args {
+    let matches = Args::command().get_matches();
+    let mut args = Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    // Read here rather than through clap's `env`, which would count the
+    // variable as a --blockchain flag and make it conflict with --selftest
+    // and --compare-chains
+    if matches.value_source("blockchain") == Some(ValueSource::DefaultValue) {
+        if let Some(blockchain) = std::env::var("BV_BLOCKCHAIN")
+            .ok()
+            .filter(|value| !value.is_empty())
+        {
+            args.blockchain = blockchain;
+        }
+    }
+    args
+}
+
+fn find_chain(name: &str) -> Option<&'static Chain> {
+    CHAINS.iter().find(|chain| chain.name == name)
+}
+
+// The chains named by --blockchain: one chain, or every chain for "all" and
+// "auto"
+fn selected_chains(name: &str) -> Vec<&'static Chain> {
+    if name == "all" || name == "auto" {
+        return CHAINS.iter().collect();
+    }
+
+    match find_chain(name) {
+        Some(chain) => vec![chain],
+        None => {
+            eprintln!("Unsupported blockchain type: {}", name);
+            process::exit(1);
+        }
+    }
+}
+
+fn validate_address(chain: &Chain, address: &str, options: &ValidationOptions) -> ValidationResult {
+    let mut result = ValidationResult::new();
+    validate_into(chain, address, options, &mut result);
+    result
+}
+
+// Time `repeat` rounds of validating against every chain, on stderr so that
+// the output is the same as without --repeat
+fn print_benchmark(
+    chains: &[&'static Chain],
+    address: &str,
+    options: &ValidationOptions,
+    repeat: u32,
+) {
+    let mut scratch = ValidationResult::new();
+    let start = Instant::now();
+    for _ in 0..repeat {
+        for chain in chains {
+            validate_into(chain, address, options, &mut scratch);
+        }
+    }
+    let total = start.elapsed();
+    let runs = repeat.saturating_mul(chains.len() as u32);
+    eprintln!(
+        "Benchmark: {} validations in {:?} ({:?} each)",
+        runs,
+        total,
+        total / runs
+    );
+}
+
+// Validate into a caller-owned result, reusing the allocations of its check
+// and warning lists, so a batch can validate line after line into one result
+fn validate_into(
+    chain: &Chain,
+    address: &str,
+    options: &ValidationOptions,
+    result: &mut ValidationResult,
+) {
+    result.clear();
+
+    // An empty address would otherwise fail every check for unrelated reasons
+    if address.trim().is_empty() {
+        result.add_check(
+            CheckCode::NonEmpty,
+            "Non-empty",
+            false,
+            "empty address".to_string(),
+        );
+        return;
+    }
+    // Decoding cost grows with the input, and no address comes near the limit
+    let length = address.trim().chars().count();
+    if options.max_input_length > 0 && length > options.max_input_length {
+        result.add_check(
+            CheckCode::Length,
+            "Input length",
+            false,
+            format!(
+                "input too long to be a valid address ({} chars, limit {})",
+                length, options.max_input_length
+            ),
+        );
+        return;
+    }
+
+    match chain.format {
+        Format::Eth {
+            chain_id,
+            address_bytes,
+            eip1191,
+        } => validate_eth_address(
+            address,
+            address_bytes,
+            chain_id.filter(|_| eip1191),
+            options,
+            result,
+        ),
+        Format::Btc(params) => validate_btc_address(address, params, options, result),
+        Format::Dcr => decred::validate_dcr_address(address, result),
+        Format::Fil => filecoin::validate_fil_address(address, options, result),
+        Format::Sol => validate_sol_address(address, options, result),
+        Format::Move => move_vm::validate_move_address(address, result),
+        Format::Ens => ens::validate_ens_name(address, result),
+        Format::Iota(hrps) => iota::validate_iota_address(address, hrps, result),
+        Format::Cash => cashaddr::validate_bch_address(address, options, result),
+    }
+
+    // Decred's configured parameters only cover its mainnet
+    let chain_network = match chain.format {
+        Format::Eth {
+            chain_id: Some(id), ..
+        } => eth_network(id),
+        Format::Dcr => Some(Network::Mainnet),
+        _ => None,
+    };
+    if let (true, Some(network)) = (result.valid, chain_network) {
+        result.network.get_or_insert(network);
+    }
+    // A --version-byte override says nothing about the network
+    if let Format::Btc(params) = chain.format {
+        if result.valid && options.version_byte.is_none() {
+            result.network = btc_network(address, params);
+        }
+    }
+    if options.annotations && result.valid {
+        annotate(chain, address, result);
+    }
+    if options.mainnet_only {
+        let (mainnet, message) = match result.network {
+            _ if !result.valid => (true, "skipped (invalid address)"),
+            Some(Network::Mainnet) => (true, "true"),
+            Some(Network::Testnet) => (false, "false (testnet address rejected by --mainnet-only)"),
+            // A production gate can't pass what it can't confirm
+            None => (
+                false,
+                "false (the address doesn't say which network it is for)",
+            ),
+        };
+        result.add_check(
+            CheckCode::Network,
+            "Mainnet network",
+            mainnet,
+            message.to_string(),
+        );
+    }
+    if let Some(requested) = options.network {
+        check_network(requested, result);
+    }
+    if let (Some(required), Format::Btc(params)) = (options.require_type, chain.format) {
+        check_type(required, address_type(address, params), result);
+    }
+    if let (Some(key), Format::Btc(params)) = (&options.pubkey, chain.format) {
+        check_pubkey(key, address_type(address, params), result);
+    }
+    if let Some(policy) = &options.policy {
+        policy::check(policy, chain, address, result);
+    }
+
+    // Without its prefix an address is short by the prefix, not truncated
+    let prefix_failed = result
+        .details
+        .iter()
+        .any(|check| check.code == CheckCode::Prefix && !check.passed);
+    if let Some((offset, unit)) = result.length_miss.filter(|_| !prefix_failed) {
+        if offset.unsigned_abs() <= options.threshold {
+            hint_near_miss(offset, unit, result);
+        }
+    }
+
+    if let Some(expected) = &options.equals {
+        let normalized = normalize_address(chain, address, options);
+        let equal = normalized == normalize_address(chain, expected, options);
+        result.add_check(
+            CheckCode::Equals,
+            "Equals expected address",
+            equal,
+            if equal {
+                "true".to_string()
+            } else {
+                format!("false (expected: {})", expected.trim())
+            },
+        );
+    }
+    if options.canonical_only {
+        check_canonical(chain, address, options, result);
+    }
+    if let Some(pattern) = &options.matches {
+        check_pattern(pattern, address.trim(), result);
+    }
+}
+
+fn check_canonical(
+    chain: &Chain,
+    address: &str,
+    options: &ValidationOptions,
+    result: &mut ValidationResult,
+) {
+    if !result.valid {
+        result.add_check(
+            CheckCode::Canonical,
+            "Canonical form",
+            true,
+            "skipped (invalid address)".to_string(),
+        );
+        return;
+    }
+    let canonical = canonical_address(chain, address, options);
+    let is_canonical = address.trim() == canonical;
+    result.add_check(
+        CheckCode::Canonical,
+        "Canonical form",
+        is_canonical,
+        if is_canonical {
+            "true".to_string()
+        } else {
+            format!("false (expected: {})", canonical)
+        },
+    );
+}
+
+fn check_pattern(pattern: &Regex, address: &str, result: &mut ValidationResult) {
+    let name = format!("Matches pattern ({})", pattern);
+    if !result.valid {
+        result.add_check(
+            CheckCode::Pattern,
+            &name,
+            true,
+            "skipped (invalid address)".to_string(),
+        );
+        return;
+    }
+    let matched = pattern.is_match(address);
+    result.add_check(CheckCode::Pattern, &name, matched, format!("{}", matched));
+}
+
+fn check_pubkey(
+    key: &k256::PublicKey,
+    actual: Option<BtcAddressType>,
+    result: &mut ValidationResult,
+) {
+    let script = match (actual, &result.payload) {
+        (Some(actual), Some(payload)) => derive::pubkey_script(key, actual, payload),
+        _ => None,
+    };
+    match (actual, script) {
+        _ if !result.valid => result.add_check(
+            CheckCode::PublicKey,
+            "Public key",
+            true,
+            "skipped (invalid address)".to_string(),
+        ),
+        (_, Some(script)) => result.add_check(
+            CheckCode::PublicKey,
+            "Public key",
+            true,
+            format!("true (pays to the key as {})", script),
+        ),
+        (Some(actual), None) => result.add_check(
+            CheckCode::PublicKey,
+            "Public key",
+            false,
+            format!(
+                "false (not derived from this key; address type: {})",
+                actual
+            ),
+        ),
+        (None, None) => result.add_check(
+            CheckCode::PublicKey,
+            "Public key",
+            false,
+            "false (not a payment address)".to_string(),
+        ),
+    }
+}
+
+// Tell a valid address of another network apart from an invalid one
+fn check_network(requested: Network, result: &mut ValidationResult) {
+    let name = format!("Requested network ({})", requested);
+    match result.network {
+        _ if !result.valid => result.add_check(
+            CheckCode::Network,
+            &name,
+            true,
+            "skipped (invalid address)".to_string(),
+        ),
+        Some(detected) if detected != requested => {
+            result.add_warning(i18n::fill(Msg::NetworkMismatch, &[&detected, &requested]));
+            result.add_check(
+                CheckCode::Network,
+                &name,
+                false,
+                format!(
+                    "false (valid {} address, but {} was requested)",
+                    detected, requested
+                ),
+            );
+        }
+        Some(_) => result.add_check(CheckCode::Network, &name, true, "true".to_string()),
+        None => result.add_check(
+            CheckCode::Network,
+            &name,
+            true,
+            "true (the format doesn't encode a network)".to_string(),
+        ),
+    }
+}
+
+fn check_type(
+    required: RequiredType,
+    actual: Option<BtcAddressType>,
+    result: &mut ValidationResult,
+) {
+    let name = format!("Required address type ({})", required);
+    match actual {
+        _ if !result.valid => result.add_check(
+            CheckCode::AddressType,
+            &name,
+            true,
+            "skipped (invalid address)".to_string(),
+        ),
+        Some(actual) if required.matches(actual) => {
+            result.add_check(CheckCode::AddressType, &name, true, "true".to_string())
+        }
+        Some(actual) => result.add_check(
+            CheckCode::AddressType,
+            &name,
+            false,
+            i18n::fill(Msg::Actual, &[&false, &actual]),
+        ),
+        None => result.add_check(
+            CheckCode::AddressType,
+            &name,
+            false,
+            "false (not a payment address)".to_string(),
+        ),
+    }
+}
+
+// A length just off a valid one is almost always a truncated address or a
+// stray character rather than another format
+fn hint_near_miss(offset: isize, unit: &str, result: &mut ValidationResult) {
+    if let Some(check) = result
+        .details
+        .iter_mut()
+        .find(|check| check.code == CheckCode::Length && !check.passed)
+    {
+        check.message = i18n::fill(
+            Msg::OffBy,
+            &[
+                &check.message.trim_end_matches(')'),
+                &format!("{:+}", offset),
+            ],
+        );
+    }
+    let count = offset.unsigned_abs();
+    let msg = if offset < 0 {
+        Msg::NearMissShort
+    } else {
+        Msg::NearMissOver
+    };
+    result.add_warning(i18n::fill(msg, &[&count, &i18n::unit(unit, count)]));
+}
+
+// Informational notes that never affect validity
+fn annotate(chain: &Chain, address: &str, result: &mut ValidationResult) {
+    if let Format::Btc(params) = chain.format {
+        let address_type = address_type(address, params);
+        if address_type == Some(BtcAddressType::P2tr) {
+            result.notes.push(i18n::text(Msg::Taproot).to_string());
+        }
+        // Only the script's hash is in the address, so a multisig looks like
+        // any other script
+        let script = match address_type {
+            Some(BtcAddressType::P2sh) => Some(("P2SH", "redeem")),
+            Some(BtcAddressType::P2wsh) => Some(("P2WSH", "witness")),
+            _ => None,
+        };
+        if let Some((name, kind)) = script {
+            result
+                .notes
+                .push(i18n::fill(Msg::ScriptHash, &[&name, &kind]));
+        }
+        if let Some((name, dust)) = address_type
+            .filter(|_| chain.name == "btc")
+            .and_then(dust_limit)
+        {
+            result
+                .notes
+                .push(i18n::fill(Msg::DustLimit, &[&name, &dust]));
+        }
+    }
+}
+
+// Bitcoin Core's dust threshold in sats for an output of this type, at the
+// default dust relay fee of 3 sat/vB. It depends on the output's size, which
+// isn't fixed for future witness versions, and anchor outputs are often
+// deliberately dust, which relay policy allows for them.
+fn dust_limit(address_type: BtcAddressType) -> Option<(&'static str, u32)> {
+    match address_type {
+        BtcAddressType::P2pkh => Some(("P2PKH", 546)),
+        BtcAddressType::P2sh => Some(("P2SH", 540)),
+        BtcAddressType::P2wpkh => Some(("P2WPKH", 294)),
+        BtcAddressType::P2wsh => Some(("P2WSH", 330)),
+        BtcAddressType::P2tr => Some(("P2TR", 330)),
+        BtcAddressType::P2a | BtcAddressType::FutureSegwit(_) => None,
+    }
+}
+
+// The form of an address used to compare two of them. Hex, bech32 and ENS
+// names are case-insensitive; base58 is not.
+fn normalize_address(chain: &Chain, address: &str, options: &ValidationOptions) -> String {
+    let mut address = address.trim();
+    if let Format::Eth { address_bytes, .. } = chain.format {
+        address = split_annotation(address, address_bytes).map_or(address, |(address, _)| address);
+    }
+    match chain.format {
+        Format::Eth { .. } if options.strip_internal_separators => address
+            .chars()
+            .filter(|&c| !is_internal_separator(c))
+            .collect::<String>()
+            .to_lowercase(),
+        Format::Eth { .. } | Format::Ens => address.to_lowercase(),
+        Format::Move => move_vm::full_address(address.strip_prefix("0x").unwrap_or(address)),
+        Format::Btc(_) | Format::Iota(_) if bech32::decode(address).is_ok() => {
+            address.to_lowercase()
+        }
+        Format::Cash if !cashaddr::is_legacy(address) => {
+            let address = address.to_lowercase();
+            if address.contains(':') {
+                address
+            } else {
+                format!("bitcoincash:{}", address)
+            }
+        }
+        _ => address.to_string(),
+    }
+}
+
+// The one form of a valid address that --canonical-only accepts: the
+// checksummed form of Ethereum-format hex, and otherwise the normalized form
+fn canonical_address(chain: &Chain, address: &str, options: &ValidationOptions) -> String {
+    let normalized = normalize_address(chain, address, options);
+    match chain.format {
+        Format::Eth {
+            chain_id, eip1191, ..
+        } => to_checksum_address(
+            normalized.strip_prefix("0x").unwrap_or(&normalized),
+            chain_id.filter(|_| eip1191),
+        ),
+        _ => normalized,
+    }
+}
+
+// Run the address through every supported validator, keeping each chain's
+// result. Input shaped like an ENS name only goes to the ENS validator, and
+// anything else skips it.
+fn validate_all(
+    address: &str,
+    options: &ValidationOptions,
+) -> Vec<(&'static Chain, ValidationResult)> {
+    let ens = ens::looks_like_ens_name(address);
+    CHAINS
+        .iter()
+        .filter(|chain| matches!(chain.format, Format::Ens) == ens)
+        .map(|chain| (chain, validate_address(chain, address, options)))
+        .collect()
+}
+
+fn print_json<T: Serialize>(value: &T) {
+    outln!(
+        "{}",
+        serde_json::to_string_pretty(value).expect("results always serialize")
+    );
+}
+
+// Write a valid address's payload as a hex line, or as bytes with `binary`
+fn print_raw(result: &ValidationResult, binary: bool) {
+    if !result.valid {
+        return;
+    }
+    match &result.payload {
+        Some(payload) if binary => {
+            let mut output = output::Output;
+            if let Err(err) = output.write_all(payload).and_then(|_| output.flush()) {
+                output::fail(err);
+            }
+        }
+        Some(payload) => outln!("{}", hex::encode(payload)),
+        None => eprintln!("This address type has no decoded payload to print"),
+    }
+}
+
+fn print_details(result: &ValidationResult, style: DetailStyle) {
+    for check in &result.details {
+        let glyph = i18n::mark(if check.passed {
+            Mark::Passed
+        } else {
+            Mark::Failed
+        });
+        let mark = match (check.passed, style.color) {
+            (true, true) => format!("\x1b[32m{}\x1b[0m", glyph),
+            (false, true) => format!("\x1b[31m{}\x1b[0m", glyph),
+            (_, false) => glyph.to_string(),
+        };
+        if style.profile {
+            outln!(
+                "- {} {}: {} ({} µs)",
+                mark,
+                i18n::check_name(&check.name),
+                check.message,
+                check.elapsed.as_micros()
+            );
+        } else {
+            outln!(
+                "- {} {}: {}",
+                mark,
+                i18n::check_name(&check.name),
+                check.message
+            );
+        }
+    }
+    for note in &result.notes {
+        outln!("- {}", i18n::fill(Msg::Note, &[note]));
+    }
+}
+
+fn display_results(chain: &Chain, result: &ValidationResult, verbose: bool, style: DetailStyle) {
+    if result.valid {
+        outln!(
+            "{} {}",
+            i18n::mark(Mark::Valid),
+            i18n::fill(Msg::Valid, &[&chain.label])
+        );
+    } else {
+        outln!(
+            "{} {}",
+            i18n::mark(Mark::Invalid),
+            i18n::fill(Msg::Invalid, &[&chain.label])
+        );
+    }
+
+    for warning in &result.warnings {
+        outln!("{} {}", i18n::mark(Mark::Warning), warning);
+    }
+
+    if verbose {
+        outln!("\n{}", i18n::text(Msg::Details));
+        if let Format::Eth {
+            chain_id: Some(chain_id),
+            ..
+        } = chain.format
+        {
+            outln!("- {}: {}", i18n::check_name("Chain ID"), chain_id);
+        }
+        if let Some(network) = result.network {
+            outln!("- {}: {}", i18n::check_name("Network"), network);
+        }
+        print_details(result, style);
+        if let Some(example) = selftest::example(chain.name).filter(|_| !result.valid) {
+            outln!("\n{}", i18n::fill(Msg::Example, &[&chain.name, &example]));
+        }
+    }
+}
+
+// The given address above its checksummed form, with carets under the
+// characters whose case differs. Only shown when a checksum check failed on
+// otherwise well-formed hex.
+fn print_checksum_diff(
+    chain: &Chain,
+    address: &str,
+    result: &ValidationResult,
+    style: DetailStyle,
+) {
+    let Format::Eth {
+        chain_id,
+        address_bytes,
+        eip1191,
+    } = chain.format
+    else {
+        return;
+    };
+    let checksum_failed = result
+        .details
+        .iter()
+        .any(|check| check.code == CheckCode::Checksum && !check.passed);
+    let address = address.trim();
+    let address = split_annotation(address, address_bytes).map_or(address, |(address, _)| address);
+    let Some(hex_part) = address.strip_prefix("0x") else {
+        return;
+    };
+    if !checksum_failed || !hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
+        return;
+    }
+
+    let expected = to_checksum_address(hex_part, chain_id.filter(|_| eip1191));
+    let differs: Vec<bool> = address
+        .chars()
+        .zip(expected.chars())
+        .map(|(given, expected)| given != expected)
+        .collect();
+    let highlight = |text: &str| -> String {
+        text.chars()
+            .zip(&differs)
+            .map(|(c, &differs)| match (differs, style.color) {
+                (true, true) => format!("\x1b[31m{}\x1b[0m", c),
+                _ => c.to_string(),
+            })
+            .collect()
+    };
+    let carets: String = differs
+        .iter()
+        .map(|&differs| if differs { '^' } else { ' ' })
+        .collect();
+
+    let count = differs.iter().filter(|&&differs| differs).count();
+    outln!(
+        "\n{}",
+        i18n::fill(Msg::ChecksumDiff, &[&count, &differs.len()])
+    );
+    // The labels are padded to one width so the carets line up
+    let (given, expected_label) = (i18n::text(Msg::DiffGiven), i18n::text(Msg::DiffExpected));
+    let width = given.chars().count().max(expected_label.chars().count());
+    outln!("  {:<width$} {}", given, highlight(address));
+    outln!("  {:<width$} {}", expected_label, highlight(&expected));
+    outln!("  {:<width$} {}", "", carets.trim_end());
+}
+
+// A grep-able summary whose format stays stable between versions:
+// `RESULT blockchain=<name> valid=<bool> checks=<n> failed=<n>`
+fn print_result_line(chain: &Chain, result: &ValidationResult) {
+    let failed = result.details.iter().filter(|check| !check.passed).count();
+    outln!(
+        "RESULT blockchain={} valid={} checks={} failed={}",
+        chain.name,
+        result.valid,
+        result.details.len(),
+        failed
+    );
+}
+
+// `eval`-able assignments: BV_VALID, BV_BLOCKCHAIN, BV_ADDRESS, BV_NETWORK
+// (empty when unknown), and BV_FAILED_CHECKS as comma-separated check codes
+fn print_env(chain: &Chain, address: &str, result: &ValidationResult) {
+    let failed: Vec<String> = result
+        .details
+        .iter()
+        .filter(|check| !check.passed)
+        .map(|check| {
+            serde_json::to_value(check.code)
+                .ok()
+                .and_then(|code| code.as_str().map(str::to_string))
+                .unwrap_or_default()
+        })
+        .collect();
+    let network = result.network.map(|network| network.to_string());
+    for (name, value) in [
+        ("BV_VALID", result.valid.to_string()),
+        ("BV_BLOCKCHAIN", chain.name.to_string()),
+        ("BV_ADDRESS", address.to_string()),
+        ("BV_NETWORK", network.unwrap_or_default()),
+        ("BV_FAILED_CHECKS", failed.join(",")),
+    ] {
+        outln!("{}={}", name, shell_quote(&value));
+    }
+}
+
+// Single-quote `value` unless every character is safe unquoted, ending and
+// reopening the quotes around each embedded quote
+fn shell_quote(value: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_,.:/@+-".contains(c);
+    if !value.is_empty() && value.chars().all(safe) {
+        return value.to_string();
+    }
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+// The names of the chains an address is valid on
+fn matches_of<'a>(
+    results: &'a [(&Chain, ValidationResult)],
+) -> impl Iterator<Item = &'static str> + 'a {
+    results
+        .iter()
+        .filter(|(_, result)| result.valid)
+        .map(|(chain, _)| chain.name)
+}
+
+// `RESULT blockchain=<all or auto> valid=<bool> matches=<names, comma
+// separated, or ->`; auto names at most one chain
+fn print_matches_line(blockchain: &str, matches: impl IntoIterator<Item = &'static str>) {
+    let matches: Vec<&str> = matches.into_iter().collect();
+    outln!(
+        "RESULT blockchain={} valid={} matches={}",
+        blockchain,
+        !matches.is_empty(),
+        if matches.is_empty() {
+            "-".to_string()
+        } else {
+            matches.join(",")
+        }
+    );
+}
+
+fn display_matches(
+    address: &str,
+    results: &[(&Chain, ValidationResult)],
+    verbose: bool,
+    style: DetailStyle,
+) {
+    let matches: Vec<&Chain> = results
+        .iter()
+        .filter(|(_, result)| result.valid)
+        .map(|(chain, _)| *chain)
+        .collect();
+
+    if matches.is_empty() {
+        outln!(
+            "{} {}",
+            i18n::mark(Mark::Invalid),
+            i18n::text(Msg::ValidForNone)
+        );
+    } else {
+        outln!("{} {}", i18n::mark(Mark::Valid), i18n::text(Msg::ValidFor));
+        for chain in &matches {
+            outln!("- {} ({})", chain.label, chain.name);
+        }
+    }
+
+    if verbose {
+        if ens::looks_like_ens_name(address) {
+            outln!("\n{}", i18n::text(Msg::DetectedEns));
+        } else {
+            outln!("\n{}", i18n::text(Msg::DetectedRaw));
+        }
+        for (chain, result) in results {
+            outln!("\n{}", i18n::fill(Msg::ChainDetails, &[&chain.label]));
+            print_details(result, style);
+        }
+    }
+}
+
+// List the chains an input is valid on, grouped by address family, and warn
+// about each way it could be sent to the wrong chain
+fn display_comparison(results: &[(&Chain, ValidationResult)]) {
+    let mut families: Vec<(&str, Vec<&Chain>)> = Vec::new();
+    for (chain, _) in results.iter().filter(|(_, result)| result.valid) {
+        let family = chain.format.family();
+        match families.iter_mut().find(|(name, _)| *name == family) {
+            Some((_, chains)) => chains.push(chain),
+            None => families.push((family, vec![chain])),
+        }
+    }
+
+    if families.is_empty() {
+        outln!(
+            "{} {}",
+            i18n::mark(Mark::Invalid),
+            i18n::text(Msg::ValidForNone)
+        );
+        return;
+    }
+    outln!("{} {}", i18n::mark(Mark::Valid), i18n::text(Msg::ValidFor));
+    for (family, chains) in &families {
+        let names: Vec<&str> = chains.iter().map(|chain| chain.name).collect();
+        outln!("- {}: {}", family, names.join(", "));
+    }
+
+    let mut risks = 0;
+    for (family, chains) in families.iter().filter(|(_, chains)| chains.len() > 1) {
+        let names: Vec<&str> = chains.iter().map(|chain| chain.name).collect();
+        outln!(
+            "{} {}",
+            i18n::mark(Mark::Warning),
+            i18n::fill(Msg::RiskSameFamily, &[&names.join(", "), family])
+        );
+        risks += 1;
+    }
+    if families.len() > 1 {
+        let names: Vec<&str> = families.iter().map(|(family, _)| *family).collect();
+        outln!(
+            "{} {}",
+            i18n::mark(Mark::Warning),
+            i18n::fill(Msg::RiskUnrelated, &[&names.join(i18n::text(Msg::And))])
+        );
+        risks += 1;
+    }
+    if risks == 0 {
+        outln!("{}", i18n::text(Msg::NoOverlap));
     }
 }
 
-fn main() {
-    let args = parse_input();
-    let validation_result = validate_address(&args);
-    display_results(&validation_result, args.verbose);
+// Spaces and dashes that some sources insert into hex for readability
+fn is_internal_separator(c: char) -> bool {
+    c.is_whitespace() || c == '-'
 }
 
-fn parse_input() -> Args {
-    Args::parse()
+// The registered Ethereum-format chain with this chain id
+fn registered_eth_chain(chain_id: u64) -> Option<&'static Chain> {
+    CHAINS.iter().find(
+        |chain| matches!(chain.format, Format::Eth { chain_id: Some(id), .. } if id == chain_id),
+    )
 }
 
-fn validate_address(args: &Args) -> ValidationResult {
-    match args.blockchain.as_str() {
-        "eth" => validate_eth_address(&args.address),
-        "btc" => validate_btc_address(&args.address),
-        "sol" => validate_sol_address(&args.address),
-        _ => {
-            eprintln!("Unsupported blockchain type: {}", args.blockchain);
-            process::exit(1);
-        }
+// A chain id named by a payment request but not registered, checked as plain
+// EIP-55 since the chain's checksum rule is unknown
+fn unregistered_eth_chain(chain_id: u64) -> Chain {
+    Chain {
+        name: "evm",
+        label: "EVM chain",
+        format: Format::Eth {
+            chain_id: Some(chain_id),
+            address_bytes: 20,
+            eip1191: false,
+        },
     }
 }
 
-fn display_results(result: &ValidationResult, verbose: bool) {
-    if result.valid {
-        println!("✅ Address is valid!");
+// Ethereum and Polygon test networks: Goerli, Sepolia, Holesky and Amoy
+const TESTNET_CHAIN_IDS: &[u64] = &[5, 11155111, 17000, 80002];
+
+// The network of an Ethereum-format chain id, where it is known. Every
+// registered chain id is a mainnet.
+fn eth_network(chain_id: u64) -> Option<Network> {
+    if TESTNET_CHAIN_IDS.contains(&chain_id) {
+        Some(Network::Testnet)
+    } else if registered_eth_chain(chain_id).is_some() {
+        Some(Network::Mainnet)
     } else {
-        println!("❌ Invalid address!");
+        None
     }
+}
 
-    if verbose {
-        println!("\nValidation details:");
-        for (check, result) in &result.details {
-            println!("- {}: {}", check, result);
-        }
+// Clipboard annotations after a complete address, as in `0xAb...2 (Ethereum)`
+// or `0xAb...2#mainnet`: the address and its annotation, but only when the
+// text before the whitespace or `#` is a full-length hex address
+fn split_annotation(address: &str, address_bytes: usize) -> Option<(&str, &str)> {
+    let end = 2 + address_bytes * 2;
+    let (head, tail) = (address.get(..end)?, address.get(end..)?);
+    let hex = head.strip_prefix("0x")?;
+    let separated = tail.starts_with(|c: char| c.is_whitespace() || c == '#');
+    if !separated || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
     }
+    Some((head, tail.trim()))
 }
 
-fn validate_eth_address(address: &str) -> ValidationResult {
-    let mut result = ValidationResult::new();
+// `checksum_chain_id` selects EIP-1191 checksums instead of plain EIP-55
+fn validate_eth_address(
+    address: &str,
+    address_bytes: usize,
+    checksum_chain_id: Option<u64>,
+    options: &ValidationOptions,
+    result: &mut ValidationResult,
+) {
+    let trimmed = address.trim();
+    if let Some((address, annotation)) = split_annotation(trimmed, address_bytes) {
+        result.notes.push(i18n::fill(
+            Msg::RemovedAnnotation,
+            &[&format!("{:?}", annotation)],
+        ));
+        return validate_eth_address(address, address_bytes, checksum_chain_id, options, result);
+    }
+
+    // Report separators on their own rather than as bad hex and a bad length,
+    // unless --validate-all-checks asks for every check anyway
+    if let Some(position) = trimmed.find(is_internal_separator) {
+        if options.strip_internal_separators {
+            let stripped: String = trimmed
+                .chars()
+                .filter(|&c| !is_internal_separator(c))
+                .collect();
+            return validate_eth_address(
+                &stripped,
+                address_bytes,
+                checksum_chain_id,
+                options,
+                result,
+            );
+        }
+
+        let separator = trimmed[position..].chars().next().unwrap_or(' ');
+        result.add_check(
+            CheckCode::Separators,
+            "No internal separators",
+            false,
+            format!(
+                "found {:?} at position {} (use --strip-internal-separators to remove them)",
+                separator, position
+            ),
+        );
+        if !options.all_checks {
+            return;
+        }
+    }
 
     // Check if it starts with 0x
     let starts_with_0x = address.starts_with("0x");
     result.add_check(
+        CheckCode::Prefix,
         "Starts with 0x",
         starts_with_0x,
         format!("{}", starts_with_0x),
     );
 
-    // Check length (0x + 40 hex chars)
-    let correct_length = address.len() == 42;
+    // Short hex such as 0x0 is a number printed by buggy tooling rather than
+    // a mangled address, so its digit count says more than a length in chars
+    let expected_digits = address_bytes * 2;
+    let short_hex = address.strip_prefix("0x").filter(|digits| {
+        digits.len() < expected_digits && digits.chars().all(|c| c.is_ascii_hexdigit())
+    });
+    if let Some(digits) = short_hex {
+        result.length_miss = Some((digits.len() as isize - expected_digits as isize, "chars"));
+        result.add_check(
+            CheckCode::Length,
+            &format!("Length ({} hex digits)", expected_digits),
+            false,
+            i18n::fill(
+                Msg::TooShort,
+                &[
+                    &digits.len(),
+                    &i18n::unit("hex digits", digits.len()),
+                    &expected_digits,
+                ],
+            ),
+        );
+    } else {
+        // Check length (0x + two hex chars per byte)
+        result.add_length_check(
+            Lengths::Exactly(2 + address_bytes * 2),
+            "chars",
+            address.len(),
+        );
+    }
+
+    // Most malformed input in a batch fails here; decoding and hashing it
+    // would only repeat that it isn't an address
+    if !result.valid && !options.all_checks {
+        return;
+    }
+
+    // Check if it's valid hex. A 0X prefix has already failed above, but the
+    // body is still judged on its own under --validate-all-checks.
+    let hex_part = strip_hex_prefix(address);
+    let offset = address.len() - hex_part.len();
+    let invalid_char = hex_part
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii_hexdigit());
+    let is_valid_hex = invalid_char.is_none();
+    if is_valid_hex {
+        result.payload = hex::decode(hex_part).ok();
+    }
     result.add_check(
-        "Length (42 chars)",
-        correct_length,
-        format!("{} (actual: {})", correct_length, address.len()),
+        CheckCode::HexCharacters,
+        "Valid hex characters",
+        is_valid_hex,
+        match invalid_char {
+            Some((i, c)) => format!("invalid hex character '{}' at position {}", c, i + offset),
+            None => "true".to_string(),
+        },
     );
 
-    // Check if it's valid hex
-    if let Some(hex_part) = address.strip_prefix("0x") {
-        let is_valid_hex = hex::decode(hex_part).is_ok();
+    // Check checksum for mixed-case addresses
+    let standard = if checksum_chain_id.is_some() {
+        "EIP-1191"
+    } else {
+        "EIP-55"
+    };
+    let checksum_name = match checksum_chain_id {
+        Some(chain_id) => format!("{} checksum (chain id {})", standard, chain_id),
+        None => format!("{} checksum", standard),
+    };
+    // Only reached with a bad length under --validate-all-checks
+    if !is_valid_hex || hex_part.len() != address_bytes * 2 {
         result.add_check(
-            "Valid hex characters",
-            is_valid_hex,
-            format!("{}", is_valid_hex),
+            CheckCode::Checksum,
+            &checksum_name,
+            true,
+            if is_valid_hex {
+                "skipped (invalid length)".to_string()
+            } else {
+                "skipped (invalid hex)".to_string()
+            },
         );
-
-        // Check checksum for mixed-case addresses
-        if hex_part.chars().any(|c| c.is_uppercase()) {
-            let checksum_valid = validate_eth_checksum(address);
+    } else if hex_part.chars().any(|c| c.is_uppercase()) {
+        let checksum_valid = validate_eth_checksum(address, checksum_chain_id);
+        result.add_check(
+            CheckCode::Checksum,
+            &checksum_name,
+            checksum_valid,
+            format!("{}", checksum_valid),
+        );
+    } else {
+        // All-digit addresses have no letters to carry a checksum
+        let has_letters = hex_part.chars().any(|c| c.is_ascii_alphabetic());
+        if options.strict && has_letters {
             result.add_check(
-                "EIP-55 checksum",
-                checksum_valid,
-                format!("{}", checksum_valid),
+                CheckCode::Checksum,
+                &checksum_name,
+                false,
+                format!(
+                    "address is not {} checksummed; provide the checksummed form",
+                    standard
+                ),
             );
         } else {
             result.add_check(
-                "EIP-55 checksum",
+                CheckCode::Checksum,
+                &checksum_name,
                 true,
-                "skipped (all lowercase)".to_string(),
+                i18n::text(Msg::SkippedLowercase).to_string(),
             );
         }
-    }
 
-    result
+        if has_letters {
+            result.add_warning(i18n::fill(
+                Msg::AllLowercase,
+                &[&to_checksum_address(hex_part, checksum_chain_id)],
+            ));
+        }
+    }
 }
 
-fn validate_eth_checksum(address: &str) -> bool {
-    let address = address.strip_prefix("0x").unwrap();
-    let address_lower = address.to_lowercase();
-    
+// Mixed-case checksummed form of a hex address body. The Keccak-256 hash covers
+// at most 64 hex digits, which bounds the address sizes this can checksum.
+fn to_checksum_address(hex_part: &str, chain_id: Option<u64>) -> String {
+    let address_lower = hex_part.to_lowercase();
+
+    // EIP-1191 hashes the chain id and prefixed address; EIP-55 hashes the bare hex
     let mut hasher = Keccak256::new();
+    if let Some(chain_id) = chain_id {
+        hasher.update(format!("{}0x", chain_id).as_bytes());
+    }
     hasher.update(address_lower.as_bytes());
     let hash = hasher.finalize();
-    
-    address.chars().zip(address_lower.chars()).enumerate().all(|(i, (actual, lower))| {
-        if lower.is_digit(16) {
-            true
-        } else {
-            let hash_val = hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 }) & 0xf;
-            (hash_val >= 8) == actual.is_uppercase()
+
+    let body: String = address_lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0xf;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("0x{}", body)
+}
+
+// A mixed-case address is valid only if it matches the checksummed form
+// letter for letter; digits carry no case and always match. The prefix is
+// not part of the hashed body, so its case never affects the result.
+fn validate_eth_checksum(address: &str, chain_id: Option<u64>) -> bool {
+    let hex_part = strip_hex_prefix(address);
+    to_checksum_address(hex_part, chain_id)[2..] == *hex_part
+}
+
+fn strip_hex_prefix(address: &str) -> &str {
+    address
+        .strip_prefix("0x")
+        .or_else(|| address.strip_prefix("0X"))
+        .unwrap_or(address)
+}
+
+// The kind of output a Bitcoin address pays to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BtcAddressType {
+    P2pkh,
+    P2sh,
+    P2wpkh,
+    P2wsh,
+    P2tr,
+    P2a,
+    // A well-formed program under a witness version with no defined meaning yet
+    FutureSegwit(u8),
+}
+
+impl fmt::Display for BtcAddressType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            BtcAddressType::P2pkh => "P2PKH (legacy)",
+            BtcAddressType::P2sh => "P2SH (script hash)",
+            BtcAddressType::P2wpkh => "P2WPKH (native segwit v0, 20-byte program)",
+            BtcAddressType::P2wsh => "P2WSH (native segwit v0, 32-byte program)",
+            BtcAddressType::P2tr => "P2TR (taproot, segwit v1)",
+            BtcAddressType::P2a => "P2A (pay to anchor, segwit v1)",
+            BtcAddressType::FutureSegwit(version) => {
+                return write!(
+                    f,
+                    "valid segwit (witness version {}, forward-compatible)",
+                    version
+                )
+            }
+        })
+    }
+}
+
+// The address types --require-type and --policy accept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+enum RequiredType {
+    P2pkh,
+    P2sh,
+    P2wpkh,
+    P2wsh,
+    P2tr,
+}
+
+impl RequiredType {
+    fn matches(self, address_type: BtcAddressType) -> bool {
+        matches!(
+            (self, address_type),
+            (RequiredType::P2pkh, BtcAddressType::P2pkh)
+                | (RequiredType::P2sh, BtcAddressType::P2sh)
+                | (RequiredType::P2wpkh, BtcAddressType::P2wpkh)
+                | (RequiredType::P2wsh, BtcAddressType::P2wsh)
+                | (RequiredType::P2tr, BtcAddressType::P2tr)
+        )
+    }
+}
+
+impl fmt::Display for RequiredType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            RequiredType::P2pkh => "P2PKH",
+            RequiredType::P2sh => "P2SH",
+            RequiredType::P2wpkh => "P2WPKH",
+            RequiredType::P2wsh => "P2WSH",
+            RequiredType::P2tr => "P2TR",
+        })
+    }
+}
+
+// What a bech32 human-readable part encodes on a Bitcoin-family chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HrpKind {
+    // Native segwit addresses (BIP-173, BIP-350)
+    Segwit,
+    // Litecoin MimbleWimble extension block addresses
+    Mweb,
+    // BIP-352 silent payment addresses (scan and spend public keys)
+    SilentPayment,
+}
+
+// Base58 addresses hash to 20 bytes; P2PKH loses a character when the
+// version byte and hash encode small
+const P2PKH_LENGTHS: Lengths = Lengths::Either(33, 34);
+// Testnet's larger P2SH version byte adds a character
+const P2SH_LENGTHS: Lengths = Lengths::Either(34, 35);
+// BIP-173 caps segwit addresses at 90 characters
+const SEGWIT_LENGTHS: Lengths = Lengths::AtMost(90);
+
+// Address prefixes of a Bitcoin-derived chain. Supporting a new bech32
+// prefix is an edit to `hrps`.
+#[derive(Debug)]
+struct BtcParams {
+    // Leading characters of base58 P2PKH and P2SH addresses
+    p2pkh_prefixes: &'static [char],
+    p2sh_prefixes: &'static [char],
+    testnet_p2pkh_prefixes: &'static [char],
+    testnet_p2sh_prefixes: &'static [char],
+    // Checksum hash used by base58 addresses
+    checksum: base58check::ChecksumFn,
+    // The network of each HRP; regtest counts as a test network
+    hrps: &'static [(&'static str, HrpKind, Network)],
+}
+
+const BTC_PARAMS: BtcParams = BtcParams {
+    p2pkh_prefixes: &['1'],
+    p2sh_prefixes: &['3'],
+    testnet_p2pkh_prefixes: &['m', 'n'],
+    testnet_p2sh_prefixes: &['2'],
+    checksum: base58check::double_sha256,
+    hrps: &[
+        ("bc", HrpKind::Segwit, Network::Mainnet),
+        ("sp", HrpKind::SilentPayment, Network::Mainnet),
+        ("tb", HrpKind::Segwit, Network::Testnet),
+        ("bcrt", HrpKind::Segwit, Network::Testnet),
+        ("tsp", HrpKind::SilentPayment, Network::Testnet),
+    ],
+};
+
+const LTC_PARAMS: BtcParams = BtcParams {
+    p2pkh_prefixes: &['L'],
+    p2sh_prefixes: &['M', '3'],
+    testnet_p2pkh_prefixes: &['m', 'n'],
+    testnet_p2sh_prefixes: &['Q', '2'],
+    checksum: base58check::double_sha256,
+    hrps: &[
+        ("ltc", HrpKind::Segwit, Network::Mainnet),
+        ("ltcmweb", HrpKind::Mweb, Network::Mainnet),
+        ("tltc", HrpKind::Segwit, Network::Testnet),
+        ("rltc", HrpKind::Segwit, Network::Testnet),
+        ("tmweb", HrpKind::Mweb, Network::Testnet),
+    ],
+};
+
+const GRS_PARAMS: BtcParams = BtcParams {
+    p2pkh_prefixes: &['F'],
+    p2sh_prefixes: &['3'],
+    testnet_p2pkh_prefixes: &['m', 'n'],
+    testnet_p2sh_prefixes: &['2'],
+    checksum: base58check::double_groestl512,
+    hrps: &[
+        ("grs", HrpKind::Segwit, Network::Mainnet),
+        ("tgrs", HrpKind::Segwit, Network::Testnet),
+    ],
+};
+
+// Classify a Bitcoin address. Base58 addresses are classified by prefix and
+// bech32 addresses by their decoded witness program. This does not fully
+// validate the address.
+pub fn btc_address_type(address: &str) -> Option<BtcAddressType> {
+    address_type(address, &BTC_PARAMS)
+}
+
+fn address_type(address: &str, params: &BtcParams) -> Option<BtcAddressType> {
+    if let Ok(decoded) = bech32::decode(address) {
+        return match hrp_kind(&decoded.hrp, params) {
+            Some(HrpKind::Segwit) => segwit_type(&decoded),
+            _ => None,
+        };
+    }
+
+    let first_char = address.chars().next()?;
+    if params.p2pkh_prefixes.contains(&first_char)
+        || params.testnet_p2pkh_prefixes.contains(&first_char)
+    {
+        Some(BtcAddressType::P2pkh)
+    } else if params.p2sh_prefixes.contains(&first_char)
+        || params.testnet_p2sh_prefixes.contains(&first_char)
+    {
+        Some(BtcAddressType::P2sh)
+    } else {
+        None
+    }
+}
+
+// The network of a Bitcoin-family address, from its HRP or base58 prefix
+fn btc_network(address: &str, params: &BtcParams) -> Option<Network> {
+    if let Ok(decoded) = bech32::decode(address) {
+        return params
+            .hrps
+            .iter()
+            .find(|(known, _, _)| *known == decoded.hrp)
+            .map(|(_, _, network)| *network);
+    }
+
+    let first_char = address.chars().next()?;
+    if params.testnet_p2pkh_prefixes.contains(&first_char)
+        || params.testnet_p2sh_prefixes.contains(&first_char)
+    {
+        Some(Network::Testnet)
+    } else if params.p2pkh_prefixes.contains(&first_char)
+        || params.p2sh_prefixes.contains(&first_char)
+    {
+        Some(Network::Mainnet)
+    } else {
+        None
+    }
+}
+
+fn hrp_kind(hrp: &str, params: &BtcParams) -> Option<HrpKind> {
+    params
+        .hrps
+        .iter()
+        .find(|(known, _, _)| *known == hrp)
+        .map(|(_, kind, _)| *kind)
+}
+
+// Witness version and program bytes of a decoded segwit address
+fn witness_program(decoded: &bech32::Decoded) -> Option<(u8, Vec<u8>)> {
+    let (&version, program) = decoded.data.split_first()?;
+    Some((version, bech32::convert_bits(program, 5, 8, false)?))
+}
+
+fn segwit_type(decoded: &bech32::Decoded) -> Option<BtcAddressType> {
+    let (version, program) = witness_program(decoded)?;
+    witness_type(version, &program)
+}
+
+// The address type of a witness program, if it's a well-formed one
+fn witness_type(version: u8, program: &[u8]) -> Option<BtcAddressType> {
+    match (version, program) {
+        (0, program) if program.len() == 20 => Some(BtcAddressType::P2wpkh),
+        (0, program) if program.len() == 32 => Some(BtcAddressType::P2wsh),
+        (1, program) if program.len() == 32 => Some(BtcAddressType::P2tr),
+        (1, program) if program == [0x4e, 0x73] => Some(BtcAddressType::P2a),
+        // BIP-173: versions 1-16 without a defined program stay spendable by
+        // future soft forks, so a well-formed one is still a valid address
+        (version @ 1..=16, program) if (2..=40).contains(&program.len()) => {
+            Some(BtcAddressType::FutureSegwit(version))
         }
-    })
+        _ => None,
+    }
 }
 
-fn validate_btc_address(address: &str) -> ValidationResult {
-    let mut result = ValidationResult::new();
+fn validate_btc_address(
+    address: &str,
+    params: &BtcParams,
+    options: &ValidationOptions,
+    result: &mut ValidationResult,
+) {
+    let lower = address.to_ascii_lowercase();
+    // The HRP is everything before the last '1', which the data part can't
+    // contain, so `bc1q1...` has the HRP "bc1q" rather than "bc"
+    let known_hrp = lower.rfind('1').is_some_and(|separator| {
+        params
+            .hrps
+            .iter()
+            .any(|(hrp, _, _)| *hrp == &lower[..separator])
+    });
+
+    if let Some(version_byte) = options.version_byte {
+        return validate_base58_version(address, params, version_byte, result);
+    }
+    if let Some(kind) = lightning_kind(&lower) {
+        return validate_lightning(address, kind, result);
+    }
+    if let Some(function) = descriptor::function(address.trim()) {
+        return descriptor::validate_descriptor(address.trim(), function, result);
+    }
+
+    match bech32::decode(address) {
+        Ok(decoded) => validate_btc_bech32(address, &decoded, params, result),
+        Err(err) if known_hrp => result.add_check(
+            CheckCode::Checksum,
+            "Bech32 checksum",
+            false,
+            err.to_string(),
+        ),
+        Err(_) => match raw_public_key(address) {
+            Some(kind) => result.add_check(
+                CheckCode::AddressType,
+                "Address type",
+                false,
+                format!("raw {} public key (P2PK), not an address", kind),
+            ),
+            None => validate_btc_base58(address, params, options, result),
+        },
+    }
+}
 
-    let first_char = address.chars().next();
-    let is_legacy = first_char == Some('1');
-    let is_p2sh = first_char == Some('3');
-    let is_bech32 = address.starts_with("bc1");
+// Lightning payment strings that get pasted where an on-chain address belongs
+fn lightning_kind(lower: &str) -> Option<&'static str> {
+    if lower.starts_with("lnurl1") {
+        Some("LNURL")
+    } else if ["lnbc", "lntb", "lntbs", "lnbcrt"]
+        .iter()
+        .any(|prefix| lower.starts_with(prefix))
+    {
+        Some("lightning invoice (BOLT11)")
+    } else {
+        None
+    }
+}
 
+// Lightning strings are bech32 without the 90-character limit, so the
+// checksum can still tell a mangled paste from a complete one
+fn validate_lightning(address: &str, kind: &str, result: &mut ValidationResult) {
+    let decoded = bech32::decode(address);
     result.add_check(
+        CheckCode::AddressType,
         "Address type",
-        is_legacy || is_p2sh || is_bech32,
-        format!(
-            "{}",
-            if is_legacy {
-                "Legacy (starts with 1)"
-            } else if is_p2sh {
-                "P2SH (starts with 3)"
-            } else if is_bech32 {
-                "Bech32 (starts with bc1)"
-            } else {
-                "Unknown"
-            }
-        ),
+        false,
+        format!("{}, not an on-chain address", kind),
     );
+    result.add_check(
+        CheckCode::Checksum,
+        "Bech32 checksum",
+        decoded.is_ok(),
+        match decoded {
+            Ok(decoded) => format!("true ({})", decoded.variant),
+            Err(err) => err.to_string(),
+        },
+    );
+}
 
-    let length_ok = if is_legacy {
-        address.len() == 34 || address.len() == 33
-    } else if is_p2sh {
-        address.len() == 34
-    } else if is_bech32 {
-        address.len() >= 42 && address.len() <= 62
-    } else {
-        false
+// A hex secp256k1 public key pasted in place of an address, as seen in
+// very old pay-to-pubkey (P2PK) outputs: 33 bytes with an 02/03 prefix when
+// compressed, 65 bytes with 04 when not
+fn raw_public_key(input: &str) -> Option<&'static str> {
+    let bytes = hex::decode(input.strip_prefix("0x").unwrap_or(input)).ok()?;
+    k256::PublicKey::from_sec1_bytes(&bytes).ok()?;
+    match (bytes.len(), bytes.first()) {
+        (33, Some(0x02 | 0x03)) => Some("compressed (33-byte)"),
+        (65, Some(0x04)) => Some("uncompressed (65-byte)"),
+        _ => None,
+    }
+}
+
+fn validate_btc_bech32(
+    address: &str,
+    decoded: &bech32::Decoded,
+    params: &BtcParams,
+    result: &mut ValidationResult,
+) {
+    result.add_check(
+        CheckCode::Checksum,
+        "Bech32 checksum",
+        true,
+        format!("true ({})", decoded.variant),
+    );
+
+    let kind = hrp_kind(&decoded.hrp, params);
+    let expected: Vec<&str> = params.hrps.iter().map(|(hrp, _, _)| *hrp).collect();
+    result.add_check(
+        CheckCode::Hrp,
+        "HRP",
+        kind.is_some(),
+        match kind {
+            Some(_) => format!("'{}'", decoded.hrp),
+            // A checksum-valid string from another bech32 chain, such as
+            // Cosmos, is worth describing rather than just rejecting
+            None => format!(
+                "valid bech32 with HRP '{}' and a {}-byte payload (not a recognized prefix; \
+                 expected {})",
+                decoded.hrp,
+                decoded.data.len() * 5 / 8,
+                expected.join(", ")
+            ),
+        },
+    );
+
+    match kind {
+        Some(HrpKind::Segwit) => {
+            let address_type = segwit_type(decoded);
+            result.add_check(
+                CheckCode::AddressType,
+                "Address type",
+                address_type.is_some(),
+                match address_type {
+                    Some(address_type) => address_type.to_string(),
+                    None => "Bech32 (unrecognized witness program)".to_string(),
+                },
+            );
+
+            // BIP-350: version 0 uses bech32, later versions use bech32m
+            if let Some((version, _)) = witness_program(decoded) {
+                let expected = if version == 0 {
+                    bech32::Variant::Bech32
+                } else {
+                    bech32::Variant::Bech32m
+                };
+                let variant_ok = decoded.variant == expected;
+                result.add_check(
+                    CheckCode::ChecksumVariant,
+                    "Checksum variant",
+                    variant_ok,
+                    format!(
+                        "{} (witness version {} expects {})",
+                        variant_ok, version, expected
+                    ),
+                );
+            }
+
+            result.add_length_check(SEGWIT_LENGTHS, "chars", address.len());
+
+            result.payload = witness_program(decoded).map(|(_, program)| program);
+            let program_len = result.payload.as_ref().map(Vec::len);
+            let program_ok = program_len.is_some_and(|len| (2..=40).contains(&len));
+            result.add_check(
+                CheckCode::DecodedLength,
+                "Witness program (2-40 bytes)",
+                program_ok,
+                match program_len {
+                    None => "invalid bech32 data padding".to_string(),
+                    Some(len) if len < 2 => format!("bech32 data too short ({} bytes)", len),
+                    Some(len) if len > 40 => format!("bech32 data too long ({} bytes)", len),
+                    Some(len) => i18n::fill(
+                        Msg::Actual,
+                        &[&true, &format!("{} {}", len, i18n::unit("bytes", len))],
+                    ),
+                },
+            );
+        }
+        Some(HrpKind::Mweb) => {
+            result.add_check(
+                CheckCode::AddressType,
+                "Address type",
+                true,
+                "MWEB (MimbleWimble extension block)".to_string(),
+            );
+        }
+        Some(HrpKind::SilentPayment) => validate_silent_payment(decoded, result),
+        None => result.add_warning(i18n::fill(Msg::ForeignHrp, &[&decoded.hrp])),
+    }
+}
+
+// BIP-352: a bech32m version character, then a 33-byte scan key and a
+// 33-byte spend key. Only version 0 is defined so far.
+fn validate_silent_payment(decoded: &bech32::Decoded, result: &mut ValidationResult) {
+    let Some((&version, data)) = decoded.data.split_first() else {
+        result.add_check(
+            CheckCode::Decoding,
+            "Bech32 data",
+            false,
+            "empty data part".to_string(),
+        );
+        return;
     };
 
     result.add_check(
-        "Length",
+        CheckCode::AddressType,
+        "Address type",
+        version == 0,
+        if version == 0 {
+            "Silent payment address (BIP-352, version 0)".to_string()
+        } else {
+            format!("unsupported silent payment version {}", version)
+        },
+    );
+
+    let variant_ok = decoded.variant == bech32::Variant::Bech32m;
+    result.add_check(
+        CheckCode::ChecksumVariant,
+        "Checksum variant",
+        variant_ok,
+        format!("{} (silent payments use bech32m)", variant_ok),
+    );
+
+    let payload = bech32::convert_bits(data, 5, 8, false);
+    let length_ok = payload.as_ref().is_some_and(|payload| payload.len() == 66);
+    result.payload = payload.clone();
+    result.add_check(
+        CheckCode::DecodedLength,
+        "Decoded length (66 bytes)",
         length_ok,
-        format!("{} (actual: {})", length_ok, address.len()),
+        match payload {
+            Some(payload) => i18n::fill(Msg::Actual, &[&length_ok, &payload.len()]),
+            None => "invalid bech32 data padding".to_string(),
+        },
+    );
+}
+
+fn validate_btc_base58(
+    address: &str,
+    params: &BtcParams,
+    options: &ValidationOptions,
+    result: &mut ValidationResult,
+) {
+    let address_type = address_type(address, params);
+    let is_legacy = address_type == Some(BtcAddressType::P2pkh);
+    let is_p2sh = address_type == Some(BtcAddressType::P2sh);
+
+    result.add_check(
+        CheckCode::AddressType,
+        "Address type",
+        address_type.is_some(),
+        match address_type {
+            Some(address_type) => address_type.to_string(),
+            None => "Unknown".to_string(),
+        },
     );
 
-    if is_legacy || is_p2sh {
+    if is_legacy {
+        result.add_length_check(P2PKH_LENGTHS, "chars", address.len());
+    } else if is_p2sh {
+        result.add_length_check(P2SH_LENGTHS, "chars", address.len());
+    } else {
+        result.add_check(
+            CheckCode::Length,
+            "Length",
+            false,
+            i18n::fill(Msg::Actual, &[&false, &address.len()]),
+        );
+    }
+
+    if is_legacy || is_p2sh || options.all_checks {
         let re = Regex::new(r"^[1-9A-HJ-NP-Za-km-z]+$").unwrap();
         let is_base58 = re.is_match(address);
         result.add_check(
+            CheckCode::Base58Characters,
             "Base58 characters",
             is_base58,
             format!("{}", is_base58),
         );
-    }
 
-    result
+        if is_base58 {
+            let decoded = base58check::decode(address, params.checksum);
+            result.add_check(
+                CheckCode::Checksum,
+                "Base58Check checksum",
+                decoded.is_ok(),
+                match &decoded {
+                    Ok(_) => "true".to_string(),
+                    Err(err) => err.to_string(),
+                },
+            );
+            if options.suggest && decoded == Err(base58check::Error::InvalidChecksum) {
+                suggest_typo_fix(address, params, result);
+            }
+
+            // One version byte followed by a 20-byte hash
+            if let Ok(payload) = decoded {
+                let length_ok = payload.len() == 21;
+                result.payload = Some(payload[1..].to_vec());
+                result.add_check(
+                    CheckCode::DecodedLength,
+                    "Decoded length (21 bytes)",
+                    length_ok,
+                    i18n::fill(
+                        Msg::ActualVersion,
+                        &[&length_ok, &payload.len(), &format!("0x{:02x}", payload[0])],
+                    ),
+                );
+            }
+        }
+    }
 }
 
-fn validate_sol_address(address: &str) -> ValidationResult {
-    let mut result = ValidationResult::new();
+// --version-byte: any base58check payload under the given version byte,
+// in place of the chain's own address types
+fn validate_base58_version(
+    address: &str,
+    params: &BtcParams,
+    version_byte: u8,
+    result: &mut ValidationResult,
+) {
+    let payload = match base58check::decode(address, params.checksum) {
+        Ok(payload) => {
+            result.add_check(
+                CheckCode::Checksum,
+                "Base58Check checksum",
+                true,
+                "true".to_string(),
+            );
+            payload
+        }
+        Err(err) => {
+            result.add_check(
+                CheckCode::Checksum,
+                "Base58Check checksum",
+                false,
+                err.to_string(),
+            );
+            return;
+        }
+    };
 
-    // Length check
-    let length_ok = (32..=44).contains(&address.len());
+    let version_ok = payload[0] == version_byte;
     result.add_check(
-        "Length (32-44 chars)",
-        length_ok,
-        format!("{} (actual: {})", length_ok, address.len()),
+        CheckCode::AddressType,
+        &format!("Version byte (0x{:02x})", version_byte),
+        version_ok,
+        i18n::fill(
+            Msg::Actual,
+            &[&version_ok, &format!("0x{:02x}", payload[0])],
+        ),
+    );
+
+    let payload_ok = payload.len() > 1;
+    result.add_check(
+        CheckCode::DecodedLength,
+        "Decoded length",
+        payload_ok,
+        format!(
+            "{} ({} bytes after the version byte)",
+            payload_ok,
+            payload.len() - 1
+        ),
     );
+    result.payload = Some(payload[1..].to_vec());
+}
+
+// Warn where a single substituted character would make the checksum pass,
+// keeping only fixes that still give a known address type
+fn suggest_typo_fix(address: &str, params: &BtcParams, result: &mut ValidationResult) {
+    let fixes: Vec<(usize, char, String)> =
+        base58check::single_substitutions(address, params.checksum)
+            .into_iter()
+            .map(|(position, replacement)| {
+                let mut fixed = address.to_string();
+                fixed.replace_range(position..position + 1, &replacement.to_string());
+                (position, replacement, fixed)
+            })
+            .filter(|(_, _, fixed)| address_type(fixed, params).is_some())
+            .collect();
+
+    match fixes.as_slice() {
+        [] => result.add_warning(i18n::text(Msg::NoSingleFix).to_string()),
+        [(position, replacement, fixed)] => result.add_warning(i18n::fill(
+            Msg::LikelyTypo,
+            &[
+                position,
+                replacement,
+                &&address[*position..position + 1],
+                fixed,
+            ],
+        )),
+        _ => {
+            let mut positions: Vec<String> = fixes
+                .iter()
+                .map(|(position, _, _)| position.to_string())
+                .collect();
+            positions.dedup();
+            result.add_warning(i18n::fill(Msg::SeveralFixes, &[&positions.join(", ")]));
+        }
+    }
+}
+
+fn validate_sol_address(address: &str, options: &ValidationOptions, result: &mut ValidationResult) {
+    // 32 bytes in base58: 44 characters, fewer with leading zero bytes
+    result.add_length_check(Lengths::Range(32, 44), "chars", address.len());
 
     // Base58 pattern check
     let re = Regex::new(r"^[1-9A-HJ-NP-Za-km-z]+$").unwrap();
     let is_base58 = re.is_match(address);
     result.add_check(
+        CheckCode::Base58Characters,
         "Base58 characters",
         is_base58,
         format!("{}", is_base58),
@@ -220,22 +3154,23 @@ fn validate_sol_address(address: &str) -> ValidationResult {
     // First character check
     let first_char_ok = address.starts_with(|c: char| ('1'..='5').contains(&c));
     result.add_check(
+        CheckCode::FirstCharacter,
         "First character (1-5)",
         first_char_ok,
-        format!(
-            "{} (actual: {})",
-            first_char_ok,
-            address.chars().next().unwrap_or(' ')
+        i18n::fill(
+            Msg::Actual,
+            &[&first_char_ok, &address.chars().next().unwrap_or(' ')],
         ),
     );
 
     // Base58 decoding check
-    if result.valid {
+    if result.valid || options.all_checks {
         let decode_result = bs58::decode(address).into_vec();
         let is_valid_encoding = decode_result.is_ok();
-        let is_correct_length = decode_result.as_ref().map_or(false, |v| v.len() == 32);
+        let is_correct_length = decode_result.as_ref().is_ok_and(|v| v.len() == 32);
         
         result.add_check(
+            CheckCode::Decoding,
             "Base58 decoding",
             is_valid_encoding,
             format!("{}", is_valid_encoding),
@@ -243,18 +3178,304 @@ fn validate_sol_address(address: &str) -> ValidationResult {
         
         if is_valid_encoding {
             result.add_check(
+                CheckCode::DecodedLength,
                 "Decoded length (32 bytes)",
                 is_correct_length,
-                format!(
-                    "{} (actual: {})",
-                    is_correct_length,
-                    decode_result.unwrap().len()
+                i18n::fill(
+                    Msg::Actual,
+                    &[&is_correct_length, &decode_result.as_ref().unwrap().len()],
                 ),
             );
         }
+
+        // Wallet keys are curve points; PDAs are deliberately off the curve
+        result.payload = decode_result.ok();
+        if !is_correct_length || !(options.on_curve || options.classify) {
+            return;
+        }
+        let bytes: [u8; 32] = result.payload.clone().unwrap().try_into().unwrap();
+        let on_curve = CompressedEdwardsY(bytes).decompress().is_some();
+        if options.on_curve {
+            result.add_check(
+                CheckCode::OnCurve,
+                "On ed25519 curve",
+                on_curve,
+                if on_curve {
+                    "true (wallet key)".to_string()
+                } else {
+                    "false (off-curve, likely a program-derived address)".to_string()
+                },
+            );
+        }
+        // Program ids are ordinary keypairs too, so the curve can't tell a
+        // program from a wallet; only PDAs are reliably off it
+        if options.classify && result.valid {
+            let msg = if on_curve {
+                Msg::OnCurve
+            } else {
+                Msg::OffCurve
+            };
+            result.notes.push(i18n::text(msg).to_string());
+        }
     }
+}
 
-    result
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The examples from EIP-55: all caps, all lowercase, then mixed case
+    const EIP55_VECTORS: &[&str] = &[
+        "0x52908400098527886E0F7030069857D2E4169EE7",
+        "0x8617E340B3D01FA5F11F306F4090FD50E238070D",
+        "0xde709f2102306220921060314715629080e2fb77",
+        "0x27b1fdb04752bbc536007a920d24acb045561c26",
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn eip55_vectors_checksum_to_themselves() {
+        for vector in EIP55_VECTORS {
+            assert_eq!(to_checksum_address(&vector[2..], None), *vector);
+            assert!(validate_eth_checksum(vector, None), "{}", vector);
+        }
+    }
+
+    #[test]
+    fn eip55_rejects_a_single_letter_in_the_wrong_case() {
+        // The last letter of 0x5aAeb...BeAed, uppercased
+        assert!(!validate_eth_checksum(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD",
+            None
+        ));
+    }
+
+    #[test]
+    fn eth_checksum_ignores_the_case_of_the_prefix() {
+        let valid = "0X5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        let flipped = "0X5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+        let lowercase = "0X5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+        assert!(validate_eth_checksum(valid, None));
+        assert!(!validate_eth_checksum(flipped, None));
+
+        // The prefix check rejects 0X, so only --validate-all-checks gets as
+        // far as the checksum
+        let chain = find_chain("eth").unwrap();
+        let options = ValidationOptions {
+            all_checks: true,
+            ..Default::default()
+        };
+        let checksum = |address| {
+            let result = validate_address(chain, address, &options);
+            assert!(!result.valid);
+            let check = result
+                .details
+                .iter()
+                .find(|check| check.code == CheckCode::Checksum)?;
+            Some((check.passed, check.message.clone()))
+        };
+        assert_eq!(checksum(valid), Some((true, "true".to_string())));
+        assert_eq!(checksum(flipped), Some((false, "false".to_string())));
+        assert_eq!(
+            checksum(lowercase),
+            Some((true, "skipped (all lowercase)".to_string()))
+        );
+    }
+
+    #[test]
+    fn short_hex_still_runs_every_check_under_validate_all_checks() {
+        let chain = find_chain("eth").unwrap();
+        let options = ValidationOptions {
+            all_checks: true,
+            ..Default::default()
+        };
+        let result = validate_address(chain, "0x0", &options);
+        let codes: Vec<CheckCode> = result.details.iter().map(|check| check.code).collect();
+        assert_eq!(
+            codes,
+            [
+                CheckCode::Prefix,
+                CheckCode::Length,
+                CheckCode::HexCharacters,
+                CheckCode::Checksum
+            ]
+        );
+        assert_eq!(
+            result.details[1].message,
+            "too short (1 hex digit, expected 40)"
+        );
+    }
+
+    #[test]
+    fn eth_format_checks_the_length_its_chain_declares() {
+        // No registered 0x chain has other than 20 bytes, so this one stands in
+        let chain = Chain {
+            name: "wide",
+            label: "32-byte chain",
+            format: Format::Eth {
+                chain_id: None,
+                address_bytes: 32,
+                eip1191: false,
+            },
+        };
+        let options = ValidationOptions::default();
+        let hex = "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed5aaeb6053f3e94c9b9a09f33";
+        let checksummed = to_checksum_address(hex, None);
+        assert!(validate_address(&chain, &format!("0x{}", hex), &options).valid);
+        assert!(validate_address(&chain, &checksummed, &options).valid);
+
+        let twenty_bytes = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert!(!validate_address(&chain, twenty_bytes, &options).valid);
+        assert!(validate_address(find_chain("eth").unwrap(), twenty_bytes, &options).valid);
+    }
+
+    #[test]
+    fn mainnet_only_rejects_a_payment_request_for_sepolia() {
+        let uri = "ethereum:0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed@11155111";
+        let request = eip681::parse(uri).unwrap();
+        let chain = unregistered_eth_chain(request.chain_id.unwrap());
+        let mut options = ValidationOptions::default();
+        let result = validate_address(&chain, request.target, &options);
+        assert!(result.valid);
+        assert_eq!(result.network, Some(Network::Testnet));
+
+        options.mainnet_only = true;
+        assert!(!validate_address(&chain, request.target, &options).valid);
+        // Neither a testnet nor a registered mainnet, so also rejected
+        let unknown = unregistered_eth_chain(424242);
+        assert!(!validate_address(&unknown, request.target, &options).valid);
+        let mainnet = find_chain("eth").unwrap();
+        assert!(validate_address(mainnet, request.target, &options).valid);
+    }
+
+    #[test]
+    fn warnings_and_check_messages_render_in_german() {
+        let chain = find_chain("eth").unwrap();
+        let style = DetailStyle {
+            profile: false,
+            color: false,
+        };
+        let output = i18n::with_lang(i18n::Lang::De, || {
+            let address = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+            let result = validate_address(chain, address, &ValidationOptions::default());
+            output::capture(|| display_results(chain, &result, true, style))
+        });
+        assert!(output.contains("Adresse ist gültig! (Ethereum)"));
+        assert!(output.contains(
+            "Adresse besteht nur aus Kleinbuchstaben und ist daher nicht durch eine Prüfsumme \
+             gegen Tippfehler geschützt. Sicherer ist die Form mit Prüfsumme: \
+             0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+        ));
+        assert!(output.contains("Länge (42 chars): true (tatsächlich: 42)"));
+        assert!(output.contains("EIP-55-Prüfsumme: übersprungen (nur Kleinbuchstaben)"));
+        assert!(!output.contains("lowercase"), "{}", output);
+    }
+
+    #[test]
+    fn near_miss_hint_is_not_given_for_a_missing_prefix() {
+        let chain = find_chain("eth").unwrap();
+        let options = ValidationOptions {
+            threshold: 2,
+            ..Default::default()
+        };
+        let result = validate_address(chain, "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed", &options);
+        assert!(!result.valid);
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+
+        // Dropping the last two digits instead is a truncation
+        let result = validate_address(chain, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA", &options);
+        assert_eq!(
+            result.warnings,
+            ["Length is 2 chars short of a valid length; the address was likely truncated"]
+        );
+    }
+
+    #[test]
+    fn separators_are_reported_alongside_the_other_checks_under_validate_all_checks() {
+        let chain = find_chain("eth").unwrap();
+        let address = "0x5aAeb6053F3E94C9 b9A09f33669435E7Ef1BeAed";
+        let codes = |options: &ValidationOptions| -> Vec<CheckCode> {
+            let result = validate_address(chain, address, options);
+            assert!(!result.valid);
+            result.details.iter().map(|check| check.code).collect()
+        };
+        assert_eq!(
+            codes(&ValidationOptions::default()),
+            [CheckCode::Separators]
+        );
+
+        let options = ValidationOptions {
+            all_checks: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            codes(&options),
+            [
+                CheckCode::Separators,
+                CheckCode::Prefix,
+                CheckCode::Length,
+                CheckCode::HexCharacters,
+                CheckCode::Checksum
+            ]
+        );
+    }
+
+    #[test]
+    fn uppercase_bech32_equals_its_lowercase_form() {
+        let chain = find_chain("btc").unwrap();
+        let lower = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        let upper = lower.to_uppercase();
+        for (address, expected) in [(lower, upper.as_str()), (upper.as_str(), lower)] {
+            let options = ValidationOptions {
+                equals: Some(expected.to_string()),
+                ..Default::default()
+            };
+            assert!(validate_address(chain, address, &options).valid);
+        }
+
+        let options = ValidationOptions {
+            equals: Some("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".to_string()),
+            ..Default::default()
+        };
+        assert!(!validate_address(chain, &upper, &options).valid);
+    }
+
+    #[test]
+    fn btc_hrp_is_everything_before_the_last_1() {
+        let chain = find_chain("btc").unwrap();
+        let options = ValidationOptions::default();
+        // Checksum-valid bech32 that starts with "bc1", under the HRP "bc1q"
+        let result = validate_address(
+            chain,
+            "bc1q1qw508d6qejxtdg4y5r3zarvary0c5xw7kdzn78v",
+            &options,
+        );
+        assert!(!result.valid);
+        let hrp = result
+            .details
+            .iter()
+            .find(|check| check.code == CheckCode::Hrp);
+        assert!(hrp.is_some_and(|check| !check.passed && check.message.contains("'bc1q'")));
+
+        let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        assert!(validate_address(chain, address, &options).valid);
+    }
+
+    #[test]
+    fn empty_input_is_rejected_on_every_chain() {
+        let options = ValidationOptions::default();
+        for chain in CHAINS {
+            for input in ["", "   ", "\t\n"] {
+                let result = validate_address(chain, input, &options);
+                assert!(!result.valid, "{} accepted {:?}", chain.name, input);
+                let codes: Vec<CheckCode> = result.details.iter().map(|check| check.code).collect();
+                assert_eq!(codes, [CheckCode::NonEmpty], "{}", chain.name);
+            }
+        }
+    }
 }
 
  /* Now, you can run the program with different blockchain addresses. Here are some examples: 
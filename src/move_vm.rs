@@ -0,0 +1,44 @@
+// Aptos and Sui (Move) account addresses: 0x followed by up to 64 hex digits.
+// Shorter forms are zero-padded on the left, so `0x1` is the framework account.
+use crate::i18n::{self, Msg};
+use crate::{CheckCode, Lengths, ValidationResult};
+
+const HEX_DIGITS: usize = 64;
+const LENGTHS: Lengths = Lengths::Range(1, HEX_DIGITS);
+
+pub fn validate_move_address(address: &str, result: &mut ValidationResult) {
+    let starts_with_0x = address.starts_with("0x");
+    result.add_check(
+        CheckCode::Prefix,
+        "Starts with 0x",
+        starts_with_0x,
+        format!("{}", starts_with_0x),
+    );
+
+    let hex_part = address.strip_prefix("0x").unwrap_or(address);
+    result.add_length_check(LENGTHS, "hex digits", hex_part.len());
+
+    let hex_ok = hex_part.chars().all(|c| c.is_ascii_hexdigit());
+    result.add_check(
+        CheckCode::HexCharacters,
+        "Valid hex characters",
+        hex_ok,
+        format!("{}", hex_ok),
+    );
+
+    result.payload = hex::decode(&full_address(hex_part)[2..]).ok();
+
+    // A short form passes structurally, but outside of system accounts it is
+    // far more likely a truncated address than an intended one
+    if result.valid && hex_part.len() < HEX_DIGITS {
+        result.add_warning(i18n::fill(
+            Msg::ShortMove,
+            &[&hex_part.len(), &HEX_DIGITS, &full_address(hex_part)],
+        ));
+    }
+}
+
+// The zero-padded, lowercase 0x form of an address's hex digits
+pub fn full_address(hex_part: &str) -> String {
+    format!("0x{:0>width$}", hex_part.to_lowercase(), width = HEX_DIGITS)
+}
@@ -0,0 +1,93 @@
+// Where results are written: stdout, or the file given with --output.
+// Progress bars and diagnostics stay on stderr either way, so scripts can
+// keep stdout for themselves.
+use std::fmt;
+use std::fs::File;
+use std::io::{self, IsTerminal, LineWriter, Write};
+use std::process;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+static FILE: OnceLock<Mutex<LineWriter<File>>> = OnceLock::new();
+
+// Tests read back what their thread wrote instead of sending it to stdout
+#[cfg(test)]
+thread_local! {
+    static CAPTURED: std::cell::RefCell<Option<Vec<u8>>> = const { std::cell::RefCell::new(None) };
+}
+
+// println! for results
+macro_rules! outln {
+    () => {
+        $crate::output::line(format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::line(format_args!($($arg)*))
+    };
+}
+
+// Send results to `file` from now on. Lines are written as they complete,
+// so a batch streams into the file rather than arriving at exit.
+pub fn set_file(file: File) {
+    let _ = FILE.set(Mutex::new(LineWriter::new(file)));
+}
+
+pub fn is_terminal() -> bool {
+    FILE.get().is_none() && io::stdout().is_terminal()
+}
+
+pub fn line(args: fmt::Arguments) {
+    if let Err(err) = writeln!(Output, "{}", args) {
+        fail(err);
+    }
+}
+
+// Stop after a failed write. A reader that went away, as `head` does once it
+// has its lines, ends the run quietly with the status a SIGPIPE would give.
+pub fn fail(err: io::Error) -> ! {
+    if err.kind() == io::ErrorKind::BrokenPipe {
+        process::exit(141);
+    }
+    eprintln!("Failed to write output: {}", err);
+    process::exit(1);
+}
+
+// The results `f` writes, as a test sees them
+#[cfg(test)]
+pub fn capture(f: impl FnOnce()) -> String {
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(Vec::new()));
+    f();
+    let written = CAPTURED.with(|captured| captured.borrow_mut().take());
+    String::from_utf8(written.unwrap_or_default()).expect("results are UTF-8")
+}
+
+// A writer for the results, for output that isn't written line by line
+pub struct Output;
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        #[cfg(test)]
+        if CAPTURED.with(|captured| match captured.borrow_mut().as_mut() {
+            Some(bytes) => {
+                bytes.extend_from_slice(buf);
+                true
+            }
+            None => false,
+        }) {
+            return Ok(buf.len());
+        }
+        match FILE.get() {
+            Some(file) => file
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .write(buf),
+            None => io::stdout().write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match FILE.get() {
+            Some(file) => file.lock().unwrap_or_else(PoisonError::into_inner).flush(),
+            None => io::stdout().flush(),
+        }
+    }
+}
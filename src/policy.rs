@@ -0,0 +1,110 @@
+// --policy: which address types and networks each chain accepts, declared
+// once in a JSON file such as
+//
+//   { "btc": { "types": ["p2wpkh", "p2tr"], "networks": ["mainnet"] },
+//     "eth": { "networks": ["mainnet"] } }
+//
+// An address that is well-formed but outside the policy fails a separate
+// "policy" check, so violations read apart from structural failures.
+use crate::i18n::{self, Msg};
+use crate::{
+    address_type, find_chain, Chain, CheckCode, Format, Network, RequiredType, ValidationResult,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainPolicy {
+    // Accepted address types, for Bitcoin-family chains; any type if absent
+    types: Option<Vec<RequiredType>>,
+    // Accepted networks; any network if absent
+    networks: Option<Vec<Network>>,
+}
+
+// Policies by chain name; chains without one accept any valid address
+#[derive(Debug, Clone, Default)]
+pub struct Policy(HashMap<String, ChainPolicy>);
+
+pub fn load(path: &str) -> Result<Policy, String> {
+    let contents = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let policies: HashMap<String, ChainPolicy> =
+        serde_json::from_str(&contents).map_err(|err| format!("invalid policy: {}", err))?;
+    for (name, policy) in &policies {
+        let chain = find_chain(name).ok_or(format!("unknown blockchain '{}'", name))?;
+        if policy.types.is_some() && !matches!(chain.format, Format::Btc(_)) {
+            return Err(format!(
+                "address types are only supported for Bitcoin-family chains (btc, ltc, grs), \
+                 not {}",
+                name
+            ));
+        }
+    }
+    Ok(Policy(policies))
+}
+
+fn list<T: ToString>(items: &[T]) -> String {
+    let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+    items.join(" or ")
+}
+
+// Add policy checks for `chain` to a structurally validated result
+pub fn check(policy: &Policy, chain: &Chain, address: &str, result: &mut ValidationResult) {
+    let Some(chain_policy) = policy.0.get(chain.name) else {
+        return;
+    };
+    if !result.valid {
+        result.add_check(
+            CheckCode::Policy,
+            "Policy",
+            true,
+            "skipped (invalid address)".to_string(),
+        );
+        return;
+    }
+
+    let mut violations = Vec::new();
+    if let (Some(types), Format::Btc(params)) = (&chain_policy.types, chain.format) {
+        let actual = address_type(address, params);
+        let accepted = actual.is_some_and(|actual| types.iter().any(|t| t.matches(actual)));
+        let name = format!("Policy: address type ({})", list(types));
+        result.add_check(
+            CheckCode::Policy,
+            &name,
+            accepted,
+            match actual {
+                _ if accepted => "true".to_string(),
+                Some(actual) => format!("false (policy violation: {})", actual),
+                None => "false (policy violation: not a payment address)".to_string(),
+            },
+        );
+        if !accepted {
+            violations.push(i18n::text(Msg::PolicyAddressType));
+        }
+    }
+    if let Some(networks) = &chain_policy.networks {
+        let accepted = result
+            .network
+            .is_some_and(|network| networks.contains(&network));
+        let name = format!("Policy: network ({})", list(networks));
+        result.add_check(
+            CheckCode::Policy,
+            &name,
+            accepted,
+            match result.network {
+                _ if accepted => "true".to_string(),
+                Some(network) => format!("false (policy violation: {})", network),
+                None => "false (policy violation: network unknown)".to_string(),
+            },
+        );
+        if !accepted {
+            violations.push(i18n::text(Msg::PolicyNetwork));
+        }
+    }
+    if !violations.is_empty() {
+        result.add_warning(i18n::fill(
+            Msg::PolicyRejected,
+            &[&violations.join(i18n::text(Msg::And))],
+        ));
+    }
+}
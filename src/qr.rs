@@ -0,0 +1,62 @@
+// Addresses scanned from wallet QR codes, which wrap them in a payment URI
+// (`bitcoin:BC1Q...?amount=1`) and often uppercase bech32 so the code can use
+// the denser alphanumeric mode.
+use crate::{bech32, eip681};
+
+// URI schemes whose path is the bare address. `bitcoincash:` is kept, as it
+// is part of a CashAddr address, and `ethereum:` URIs are parsed as EIP-681.
+const SCHEMES: &[&str] = &["bitcoin:", "litecoin:", "groestlcoin:", "solana:"];
+
+pub struct Scanned {
+    pub address: String,
+    // What was done to the payload to get the address, in order
+    pub transformations: Vec<String>,
+}
+
+pub fn extract(payload: &str) -> Scanned {
+    let mut transformations = Vec::new();
+
+    let mut address = payload.trim();
+    if address.len() != payload.len() {
+        transformations.push("trimmed surrounding whitespace".to_string());
+    }
+    if eip681::is_uri(address) {
+        return Scanned {
+            address: address.to_string(),
+            transformations,
+        };
+    }
+
+    let scheme = SCHEMES.iter().find(|scheme| {
+        address
+            .get(..scheme.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+    });
+    if let Some(scheme) = scheme {
+        address = &address[scheme.len()..];
+        transformations.push(format!("removed the '{}' scheme", scheme));
+        if let Some((path, _)) = address.split_once('?') {
+            address = path;
+            transformations.push("removed the URI parameters".to_string());
+        }
+        // BIP-21 allows `bitcoin://` from clients that insist on a hierarchy
+        if let Some(path) = address.strip_prefix("//") {
+            address = path;
+        }
+    }
+
+    let uppercase = address.chars().any(|c| c.is_ascii_uppercase())
+        && !address.chars().any(|c| c.is_ascii_lowercase());
+    if uppercase && bech32::decode(address).is_ok() {
+        transformations.push("lowercased uppercase bech32".to_string());
+        return Scanned {
+            address: address.to_lowercase(),
+            transformations,
+        };
+    }
+
+    Scanned {
+        address: address.to_string(),
+        transformations,
+    }
+}
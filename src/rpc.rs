@@ -0,0 +1,149 @@
+// Optional network lookups, enabled by the "rpc" feature
+use crate::{Chain, Format};
+use serde_json::{json, Value};
+use std::thread;
+use std::time::Duration;
+
+// Attempts per request, and the wait before the first retry, which doubles
+// after each failure
+const ATTEMPTS: u32 = 3;
+const BACKOFF: Duration = Duration::from_millis(250);
+
+pub enum Activity {
+    Active(String),
+    NeverUsed,
+}
+
+// An HTTP client that retries transient failures with exponential backoff
+struct Client {
+    agent: ureq::Agent,
+}
+
+impl Client {
+    fn new(timeout: Duration) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(timeout).build(),
+        }
+    }
+
+    // GET `url`, or POST `body` to it, returning the JSON response
+    fn fetch(&self, url: &str, body: Option<&Value>) -> Result<Value, String> {
+        let mut backoff = BACKOFF;
+        let mut attempt = 1;
+        loop {
+            let result = match body {
+                Some(body) => self.agent.post(url).send_json(body),
+                None => self.agent.get(url).call(),
+            };
+            match result {
+                Ok(response) => return response.into_json().map_err(|err| err.to_string()),
+                Err(err) if attempt < ATTEMPTS && is_transient(&err) => {
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                Err(err) if attempt > 1 => {
+                    return Err(format!("{} (after {} attempts)", err, attempt))
+                }
+                Err(err) => return Err(err.to_string()),
+            }
+        }
+    }
+}
+
+// Connection failures, rate limiting and server errors may pass on retry;
+// other client errors won't
+fn is_transient(err: &ureq::Error) -> bool {
+    match err {
+        ureq::Error::Status(code, _) => *code == 429 || *code >= 500,
+        ureq::Error::Transport(_) => true,
+    }
+}
+
+// Print whether `address` has any on-chain history. Lookup failures are
+// reported but never change the validation outcome.
+pub fn report_activity(chain: &Chain, address: &str, rpc_url: &str, timeout: Duration) {
+    match check_activity(chain, address, rpc_url, timeout) {
+        Ok(Activity::Active(detail)) => outln!("Activity: active ({})", detail),
+        Ok(Activity::NeverUsed) => outln!("Activity: never used (no on-chain history)"),
+        Err(err) => outln!("Activity: unavailable ({})", err),
+    }
+}
+
+// Ethereum-format chains are queried over JSON-RPC, Bitcoin-family chains
+// through an Esplora-style REST API.
+pub fn check_activity(
+    chain: &Chain,
+    address: &str,
+    rpc_url: &str,
+    timeout: Duration,
+) -> Result<Activity, String> {
+    let client = Client::new(timeout);
+
+    match chain.format {
+        Format::Eth { .. } => eth_activity(&client, address, rpc_url),
+        Format::Btc(_) => btc_activity(&client, address, rpc_url),
+        _ => Err(format!(
+            "activity lookup is not supported for {}",
+            chain.label
+        )),
+    }
+}
+
+fn eth_activity(client: &Client, address: &str, rpc_url: &str) -> Result<Activity, String> {
+    let nonce = eth_quantity(client, rpc_url, "eth_getTransactionCount", address)?;
+    if nonce > 0 {
+        return Ok(Activity::Active(format!("nonce {}", nonce)));
+    }
+
+    // Addresses that have only ever received funds still have a zero nonce
+    let balance = eth_quantity(client, rpc_url, "eth_getBalance", address)?;
+    if balance > 0 {
+        return Ok(Activity::Active("nonzero balance".to_string()));
+    }
+
+    Ok(Activity::NeverUsed)
+}
+
+fn eth_quantity(
+    client: &Client,
+    rpc_url: &str,
+    method: &str,
+    address: &str,
+) -> Result<u128, String> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": [address, "latest"],
+    });
+    let response = client.fetch(rpc_url, Some(&request))?;
+
+    if let Some(error) = response.get("error") {
+        return Err(format!("{} failed: {}", method, error));
+    }
+    let quantity = response["result"]
+        .as_str()
+        .ok_or_else(|| format!("{} returned no result", method))?;
+    let digits = quantity.trim_start_matches("0x");
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    u128::from_str_radix(digits, 16)
+        .map_err(|err| format!("{} returned {}: {}", method, quantity, err))
+}
+
+fn btc_activity(client: &Client, address: &str, rpc_url: &str) -> Result<Activity, String> {
+    let url = format!("{}/address/{}", rpc_url.trim_end_matches('/'), address);
+    let response = client.fetch(&url, None)?;
+
+    let tx_count = |stats: &str| response[stats]["tx_count"].as_u64();
+    let confirmed = tx_count("chain_stats").ok_or("unexpected response from the API")?;
+    let total = confirmed + tx_count("mempool_stats").unwrap_or(0);
+
+    if total > 0 {
+        Ok(Activity::Active(format!("{} transactions", total)))
+    } else {
+        Ok(Activity::NeverUsed)
+    }
+}
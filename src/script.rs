@@ -0,0 +1,108 @@
+// Bitcoin output scripts (scriptPubKey) given as hex. Each standard template
+// commits to a hash or witness program that an address encodes, so a
+// recognized script maps back to exactly one mainnet address.
+use crate::{base58check, bech32, witness_type, BtcAddressType, CheckCode, ValidationResult};
+
+const OP_0: u8 = 0x00;
+const OP_1: u8 = 0x51;
+const OP_16: u8 = 0x60;
+const OP_DUP: u8 = 0x76;
+const OP_EQUALVERIFY: u8 = 0x88;
+const OP_EQUAL: u8 = 0x87;
+const OP_HASH160: u8 = 0xa9;
+const OP_CHECKSIG: u8 = 0xac;
+
+// The template a script follows and the hash or witness program it commits to
+enum Template<'a> {
+    Base58(BtcAddressType, &'a [u8]),
+    Witness(BtcAddressType, u8, &'a [u8]),
+}
+
+fn parse_template(script: &[u8]) -> Option<Template<'_>> {
+    match script {
+        [OP_DUP, OP_HASH160, 0x14, hash @ .., OP_EQUALVERIFY, OP_CHECKSIG] if hash.len() == 20 => {
+            Some(Template::Base58(BtcAddressType::P2pkh, hash))
+        }
+        [OP_HASH160, 0x14, hash @ .., OP_EQUAL] if hash.len() == 20 => {
+            Some(Template::Base58(BtcAddressType::P2sh, hash))
+        }
+        // A version opcode followed by a single push of the witness program
+        [opcode @ (OP_0 | OP_1..=OP_16), push, program @ ..]
+            if usize::from(*push) == program.len() =>
+        {
+            let version = if *opcode == OP_0 {
+                0
+            } else {
+                opcode - OP_1 + 1
+            };
+            let address_type = witness_type(version, program)?;
+            Some(Template::Witness(address_type, version, program))
+        }
+        _ => None,
+    }
+}
+
+fn encode_address(template: &Template) -> String {
+    match *template {
+        Template::Base58(address_type, hash) => {
+            let version = if address_type == BtcAddressType::P2pkh {
+                0x00
+            } else {
+                0x05
+            };
+            base58check::encode(&[&[version], hash].concat(), base58check::double_sha256)
+        }
+        Template::Witness(_, version, program) => {
+            let data =
+                bech32::convert_bits(program, 8, 5, true).expect("bytes regroup into 5-bit values");
+            let variant = if version == 0 {
+                bech32::Variant::Bech32
+            } else {
+                bech32::Variant::Bech32m
+            };
+            bech32::encode("bc", &[&[version], &data[..]].concat(), variant)
+        }
+    }
+}
+
+// The mainnet address a standard script pays to
+pub fn script_address(script_hex: &str) -> Option<String> {
+    let script = hex::decode(script_hex.trim()).ok()?;
+    parse_template(&script).map(|template| encode_address(&template))
+}
+
+pub fn validate_script(script_hex: &str) -> ValidationResult {
+    let mut result = ValidationResult::new();
+
+    let script = hex::decode(script_hex.trim());
+    result.add_check(
+        CheckCode::HexCharacters,
+        "Valid hex characters",
+        script.is_ok(),
+        match &script {
+            Ok(script) => format!("true ({} bytes)", script.len()),
+            Err(err) => err.to_string(),
+        },
+    );
+    let Ok(script) = script else {
+        return result;
+    };
+
+    let template = parse_template(&script);
+    result.add_check(
+        CheckCode::AddressType,
+        "Script template",
+        template.is_some(),
+        match &template {
+            Some(Template::Base58(address_type, _) | Template::Witness(address_type, ..)) => {
+                address_type.to_string()
+            }
+            None => "non-standard script with no address form".to_string(),
+        },
+    );
+    result.payload = template.map(|template| match template {
+        Template::Base58(_, hash) | Template::Witness(_, _, hash) => hash.to_vec(),
+    });
+
+    result
+}
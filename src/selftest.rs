@@ -0,0 +1,106 @@
+// --selftest: the fixture of tricky inputs, built into the binary, run
+// through the validators so an installed copy can confirm that each input
+// still gives its expected result
+use crate::i18n::{self, Mark};
+use crate::{find_chain, validate_address, Args, ValidationOptions};
+use clap::Parser;
+
+const VECTORS: &str = include_str!("../fixtures/tricky-addresses.txt");
+
+// The first vector expected to be valid on `chain` without any flags, as an
+// example of what its addresses look like
+pub fn example(chain: &str) -> Option<&'static str> {
+    VECTORS.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields[..] {
+            ["valid", name, input] if name == chain => Some(input),
+            _ => None,
+        }
+    })
+}
+
+// Run every vector, printing one line each, and report whether all of them
+// gave their expected result
+pub fn run() -> bool {
+    let mut total = 0;
+    let mut failed = 0;
+    for line in VECTORS.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        total += 1;
+        let outcome = run_vector(line);
+        let mark = i18n::mark(if outcome.is_ok() {
+            Mark::Passed
+        } else {
+            Mark::Failed
+        });
+        match outcome {
+            Ok(()) => outln!("{} {}", mark, line),
+            Err(err) => {
+                failed += 1;
+                outln!("{} {} ({})", mark, line, err);
+            }
+        }
+    }
+    outln!(
+        "\nSelf-test: {} of {} vectors gave the expected result",
+        total - failed,
+        total
+    );
+    failed == 0
+}
+
+// `<valid|invalid> <blockchain> <input> [--flag ...]`
+fn run_vector(line: &str) -> Result<(), String> {
+    let mut fields = line.split_whitespace();
+    let (Some(expected), Some(blockchain), Some(input)) =
+        (fields.next(), fields.next(), fields.next())
+    else {
+        return Err("malformed vector".to_string());
+    };
+    let expected = match expected {
+        "valid" => true,
+        "invalid" => false,
+        other => return Err(format!("unknown expectation '{}'", other)),
+    };
+    let chain = find_chain(blockchain).ok_or(format!("unknown blockchain '{}'", blockchain))?;
+    let args = Args::try_parse_from(
+        ["blockchain-validator", "-b", blockchain, "-a", input]
+            .into_iter()
+            .chain(fields),
+    )
+    .map_err(|err| format!("bad flags: {}", err.kind()))?;
+
+    let result = validate_address(chain, input, &ValidationOptions::from_args(&args));
+    match (expected, result.valid) {
+        (true, false) => Err("got invalid".to_string()),
+        (false, true) => Err("got valid".to_string()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_vector_gives_its_expected_result() {
+        let failures: Vec<String> = VECTORS
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                run_vector(line)
+                    .err()
+                    .map(|err| format!("{} ({})", line, err))
+            })
+            .collect();
+        assert!(
+            failures.is_empty(),
+            "failed vectors:\n{}",
+            failures.join("\n")
+        );
+    }
+}
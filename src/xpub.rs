@@ -0,0 +1,133 @@
+// BIP-32 extended keys (xpub, ypub, zpub, ...): base58check over a 78-byte
+// payload of version, depth, parent fingerprint, child number, chain code
+// and key. These are wallet keys rather than addresses.
+use crate::i18n::{self, Msg};
+use crate::{base58check, CheckCode, ValidationResult};
+
+// Version bytes and the name of the key type they mark (SLIP-132)
+const VERSIONS: &[([u8; 4], &str)] = &[
+    ([0x04, 0x88, 0xb2, 0x1e], "xpub (P2PKH or P2SH, mainnet)"),
+    ([0x04, 0x88, 0xad, 0xe4], "xprv (P2PKH or P2SH, mainnet)"),
+    ([0x04, 0x9d, 0x7c, 0xb2], "ypub (P2WPKH in P2SH, mainnet)"),
+    ([0x04, 0x9d, 0x78, 0x78], "yprv (P2WPKH in P2SH, mainnet)"),
+    ([0x04, 0xb2, 0x47, 0x46], "zpub (P2WPKH, mainnet)"),
+    ([0x04, 0xb2, 0x43, 0x0c], "zprv (P2WPKH, mainnet)"),
+    (
+        [0x02, 0x95, 0xb4, 0x3f],
+        "Ypub (multisig P2WSH in P2SH, mainnet)",
+    ),
+    ([0x02, 0xaa, 0x7e, 0xd3], "Zpub (multisig P2WSH, mainnet)"),
+    ([0x04, 0x35, 0x87, 0xcf], "tpub (P2PKH or P2SH, testnet)"),
+    ([0x04, 0x35, 0x83, 0x94], "tprv (P2PKH or P2SH, testnet)"),
+    ([0x04, 0x4a, 0x52, 0x62], "upub (P2WPKH in P2SH, testnet)"),
+    ([0x04, 0x4a, 0x4e, 0x28], "uprv (P2WPKH in P2SH, testnet)"),
+    ([0x04, 0x5f, 0x1c, 0xf6], "vpub (P2WPKH, testnet)"),
+    ([0x04, 0x5f, 0x18, 0xbc], "vprv (P2WPKH, testnet)"),
+];
+
+// The key type named by a payload's version bytes, e.g. "zpub (P2WPKH, mainnet)"
+pub fn key_type(payload: &[u8]) -> Option<&'static str> {
+    VERSIONS
+        .iter()
+        .find(|(version, _)| payload.starts_with(version))
+        .map(|(_, name)| *name)
+}
+
+pub fn is_private(key_type: &str) -> bool {
+    key_type[1..].starts_with("prv")
+}
+
+pub fn validate_extended_key(key: &str) -> ValidationResult {
+    let mut result = ValidationResult::new();
+
+    let payload = match base58check::decode(key.trim(), base58check::double_sha256) {
+        Ok(payload) => {
+            result.add_check(
+                CheckCode::Checksum,
+                "Base58Check checksum",
+                true,
+                "true".to_string(),
+            );
+            payload
+        }
+        Err(err) => {
+            result.add_check(
+                CheckCode::Checksum,
+                "Base58Check checksum",
+                false,
+                err.to_string(),
+            );
+            return result;
+        }
+    };
+
+    let length_ok = payload.len() == 78;
+    result.add_check(
+        CheckCode::DecodedLength,
+        "Decoded length (78 bytes)",
+        length_ok,
+        i18n::fill(Msg::Actual, &[&length_ok, &payload.len()]),
+    );
+    if !length_ok {
+        return result;
+    }
+
+    let kind = key_type(&payload);
+    result.add_check(
+        CheckCode::AddressType,
+        "Key type",
+        kind.is_some(),
+        match kind {
+            Some(name) => name.to_string(),
+            None => format!("unknown version bytes {}", hex::encode(&payload[..4])),
+        },
+    );
+
+    // A master key (depth 0) has no parent
+    let depth = payload[4];
+    let fingerprint = &payload[5..9];
+    let child_number = &payload[9..13];
+    let master_ok = depth != 0 || (fingerprint == [0; 4] && child_number == [0; 4]);
+    result.add_check(
+        CheckCode::Decoding,
+        "Depth, fingerprint and child number",
+        master_ok,
+        if master_ok {
+            format!(
+                "depth {}, parent fingerprint {}, child number {}",
+                depth,
+                hex::encode(fingerprint),
+                u32::from_be_bytes([
+                    child_number[0],
+                    child_number[1],
+                    child_number[2],
+                    child_number[3]
+                ])
+            )
+        } else {
+            "a depth 0 key must have a zero fingerprint and child number".to_string()
+        },
+    );
+
+    // Public keys are compressed secp256k1 points; private keys are a
+    // zero byte followed by the 32-byte secret
+    let key_data = &payload[45..78];
+    let private = kind.is_some_and(is_private);
+    let key_ok = if private {
+        key_data[0] == 0x00
+    } else {
+        key_data[0] == 0x02 || key_data[0] == 0x03
+    };
+    result.add_check(
+        CheckCode::Prefix,
+        "Key data prefix",
+        key_ok,
+        i18n::fill(Msg::Actual, &[&key_ok, &format!("{:02x}", key_data[0])]),
+    );
+    result.payload = Some(payload);
+    if private {
+        result.add_warning(i18n::text(Msg::PrivateKey).to_string());
+    }
+
+    result
+}